@@ -13,7 +13,7 @@ fn main() {
     Log::notice("The bath temperature is 44 degrees."); // Write.
     Log::warn("The refrigerator is empty."); // Write.
     Log::error("Where did you put my train pass?"); // Write.
-    panic!(Log::fatal("I haven't set an alarm clock.")); // Write.
+    panic!("{}", Log::fatal("I haven't set an alarm clock.")); // Write.
 
     // Log::flush(); // Log::Fatal() will flushes instead.
 }
@@ -1,5 +1,5 @@
-//! There is no configuration file.  
-//! 設定ファイルはありません。  
+//! The settings are written in the code.  
+//! 設定はコードに書きます。  
 
 use casual_logger::{Extension, Level, Log, Opt};
 
@@ -43,17 +43,17 @@ fn main() {
                 .str(
                     "FileNameStem",
                     &Log::get_file_name() //
-                        .unwrap_or_else(|err| err),
+                        .unwrap_or_else(|err| err.to_string()),
                 )
                 .str(
                     "Extension",
                     &Log::get_file_ext_str() //
-                        .unwrap_or_else(|err| err),
+                        .unwrap_or_else(|err| err.to_string()),
                 )
                 .int(
                     "RetentionDays",
                     Log::get_retention_days() //
-                        .unwrap_or_else(|_| 0)
+                        .unwrap_or(0)
                         .into(),
                 )
                 .str(
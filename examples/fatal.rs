@@ -7,10 +7,10 @@ fn main() {
     Log::set_file_name("test-fatal");
     Log::remove_old_logs();
 
-    // panic!(Log::fatal("Critical message!"));
+    // panic!("{}", Log::fatal("Critical message!"));
 
-    panic!(Log::fatal_t(
-        "Critical message!",
-        Table::default().int("Apple", 1)
-    ));
+    panic!(
+        "{}",
+        Log::fatal_t("Critical message!", Table::default().int("Apple", 1))
+    );
 }
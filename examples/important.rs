@@ -11,7 +11,7 @@ fn main() {
     Log::set_file_name("mischief1");
     Log::debug(&format!(
         "file_name=|{}|",
-        Log::get_file_name().unwrap_or_else(|err| err)
+        Log::get_file_name().unwrap_or_else(|err| err.to_string())
     ));
 
     // File extension.
@@ -19,7 +19,7 @@ fn main() {
     Log::set_file_ext(Extension::LogToml);
     Log::debug(&format!(
         "file_ext=|{}|",
-        Log::get_file_ext_str().unwrap_or_else(|err| err)
+        Log::get_file_ext_str().unwrap_or_else(|err| err.to_string())
     ));
 
     // Level.
@@ -27,7 +27,7 @@ fn main() {
     Log::set_level(Level::Error);
     Log::debug(&format!(
         "level=|{}|",
-        Log::get_level().unwrap_or_else(|_| DEFAULT_LOG_LEVEL)
+        Log::get_level().unwrap_or(DEFAULT_LOG_LEVEL)
     ));
 
     // File retention days.
@@ -35,7 +35,7 @@ fn main() {
     Log::set_retention_days(17);
     Log::debug(&format!(
         "retention_days=|{}|",
-        Log::get_retention_days().unwrap_or_else(|_| DEFAULT_RETENTION_DAYS)
+        Log::get_retention_days().unwrap_or(DEFAULT_RETENTION_DAYS)
     ));
 
     // Timeout seconds.
@@ -43,7 +43,7 @@ fn main() {
     Log::set_timeout_secs(70);
     Log::debug(&format!(
         "timeout_secs=|{}|",
-        Log::get_timeout_secs().unwrap_or_else(|_| DEFAULT_TIMEOUT_SECS)
+        Log::get_timeout_secs().unwrap_or(DEFAULT_TIMEOUT_SECS)
     ));
 
     // Optimize.
@@ -51,7 +51,7 @@ fn main() {
     Log::set_opt(Opt::Development);
     Log::debug(&format!(
         "opt=|{:?}|",
-        Log::get_opt().unwrap_or_else(|_| DEFAULT_OPTIMIZATION)
+        Log::get_opt().unwrap_or(DEFAULT_OPTIMIZATION)
     ));
 
     // Finish.
@@ -42,6 +42,30 @@ fn main() {
     // ファイル名に '.log' は必ず含めます。
     Log::set_file_ext(Extension::LogToml);
     //
+    // Directory where log files are placed.
+    // It is created if it does not exist. Default: ".".
+    // ログ・ファイルを置くディレクトリー。
+    // 無ければ作成します。デフォルト: "."。
+    // Log::set_dir("./logs");
+    //
+    // Maximum file size in bytes. Default: 0 (Unlimited).
+    // If exceeded, the file is rotated to
+    // 'tic-tac-toe-2020-07-11.1.log.toml', '.2', ...
+    // 最大ファイル・サイズ（バイト）。デフォルト: 0（無制限）。
+    // 超えると 'tic-tac-toe-2020-07-11.1.log.toml', '.2', ...
+    // に切り替わります。
+    // Log::set_max_file_size(10 * 1024 * 1024);
+    //
+    // Rotation period. Default: Rotation::Daily.
+    // Rotation::Hourly, Rotation::Daily, Rotation::Weekly
+    // or Rotation::Interval(chrono::Duration::minutes(30)).
+    // ローテーション周期。デフォルト: Rotation::Daily。
+    // Log::set_rotation(Rotation::Hourly);
+    //
+    // Compress rotated files to '.log.toml.gz'. Default: false.
+    // ローテーションしたファイルを '.log.toml.gz' に圧縮します。
+    // Log::set_compress(true);
+    //
     // Logs with lower priority than this level will not
     // be written.
     //
@@ -53,6 +77,36 @@ fn main() {
     // Remove old log files. This is determined by the
     // StartDate in the filename.
     Log::set_retention_days(2);
+    // Also, keep at most this number of files, and at most
+    // this total size in bytes. Default: 0 (Unlimited).
+    // The oldest files are removed first.
+    // また、ファイル数と合計サイズ（バイト）の上限です。
+    // デフォルト: 0（無制限）。古いファイルから削除します。
+    // Log::set_retention_files(10);
+    // Log::set_retention_size(100 * 1024 * 1024);
+    // Remove old log files each time a file is opened by
+    // rotation, for long-running processes. Default: false.
+    // ローテーションでファイルを開くたびに古いログ・ファイルを
+    // 削除します。長時間動くプロセス向け。デフォルト: false。
+    // Log::set_auto_remove(true);
+
+    // Limit the logs waiting to be written. Default: 0
+    // (Unlimited). When full, Overflow::Block waits, or
+    // Overflow::DropNewest, DropOldest, DropBelow(Level)
+    // drop logs, and the number dropped is logged later.
+    // 書き込みを待つログの上限。デフォルト: 0（無制限）。
+    // Log::set_queue_capacity(100_000);
+    // Log::set_overflow(Overflow::DropBelow(Level::Warn));
+
+    // Synchronize the file to the disk after writing a log
+    // at or above this level. Default: None.
+    // このレベル以上のログを書いた後、ファイルをディスクへ同期します。
+    // Log::set_sync_level(Some(Level::Error));
+
+    // Write on the calling thread before logging returns.
+    // For short-lived tools. Default: WriteMode::Background.
+    // ロギングから戻る前に呼び出し元のスレッドで書き込みます。
+    // Log::set_write_mode(WriteMode::Synchronous);
 
     // Wait for seconds logging to complete.
     // By default it's set to 30 seconds,
@@ -109,7 +163,7 @@ fn main() {
     Log::fatalln("(14)fatal-line");
 
     // Fatal is Panic! Can be used as the first argument of.
-    // panic!(Log::fatal(&format!("Invalid number=|{}|", 99)));
+    // panic!("{}", Log::fatal(&format!("Invalid number=|{}|", 99)));
 
     // Note: It's usually weird to change the level twice in
     // a program.
@@ -25,7 +25,7 @@ fn main() {
             count_1 += 1;
         }
         if let Err(msg) = sender1.send(count_1) {
-            panic!(msg);
+            std::panic::panic_any(msg);
         }
     });
 
@@ -37,7 +37,7 @@ fn main() {
             count_2 += 1;
         }
         if let Err(msg) = sender2.send(count_2) {
-            panic!(msg);
+            std::panic::panic_any(msg);
         }
     });
 
@@ -49,7 +49,7 @@ fn main() {
             count_3 += 1;
         }
         if let Err(msg) = sender3.send(count_3) {
-            panic!(msg);
+            std::panic::panic_any(msg);
         }
     });
 
@@ -60,42 +60,34 @@ fn main() {
         Log::infoln(&format!("Hello, world!! {}", i + 1));
         count_0 += 1;
     }
+    // The caller only waits for the table to be sent to the writer thread.
+    // 呼び出し元は、テーブルを書き込みスレッドへ送るまでしか待ちません。
+    let enqueue_millis = stopwatch.elapsed().as_millis();
 
     // Wait for logging to complete or to timeout.
     Log::flush();
 
     // Block.
-    let count_1 = if let Ok(count_1) = receiver1.recv() {
-        count_1
-    } else {
-        0
-    };
-    let count_2 = if let Ok(count_2) = receiver2.recv() {
-        count_2
-    } else {
-        0
-    };
-    let count_3 = if let Ok(count_3) = receiver3.recv() {
-        count_3
-    } else {
-        0
-    };
+    let count_1 = receiver1.recv().unwrap_or(0);
+    let count_2 = receiver2.recv().unwrap_or(0);
+    let count_3 = receiver3.recv().unwrap_or(0);
 
     /*
     // Fatal is Panic! Can be used as the first argument of.
-    panic!(Log::fatal(&format!(
+    panic!("{}", Log::fatal(&format!(
         "Panic successful. Performance: {} ms.",
         stopwatch.elapsed().as_millis()
     )));
     */
 
     println!(
-        "Performance     |{}|{}|{}|{}| records, {} ms.",
+        "Performance     |{}|{}|{}|{}| records, {} ms. (Enqueue {} ms.)",
         count_0,
         count_1,
         count_2,
         count_3,
         stopwatch.elapsed().as_millis(),
+        enqueue_millis,
         /*
         if let Ok(mem) = mem_info() {
             format!(
@@ -182,21 +182,21 @@ key",
         Table::default()
             .sub_aot(
                 "z1",
-                &ArrayOfTable::default()
+                ArrayOfTable::default()
                     .table(
-                        &Table::default()
+                        Table::default()
                             .int("Apple", 1)
                             .int("Alice", 18)
                             .int("Alpha", 100),
                     )
                     .table(
-                        &Table::default()
+                        Table::default()
                             .int("Banana", 2)
                             .int("Bob", 19)
                             .int("Beta", 200),
                     )
                     .table(
-                        &Table::default()
+                        Table::default()
                             .int("Cherry", 3)
                             .int("Charley", 20)
                             .int("Gamma", 300),
@@ -204,21 +204,21 @@ key",
             )
             .sub_aot(
                 "z2",
-                &ArrayOfTable::default()
+                ArrayOfTable::default()
                     .table(
-                        &Table::default()
+                        Table::default()
                             .int("The Apple", -1)
                             .int("The Alice", -18)
                             .int("The Alpha", -100),
                     )
                     .table(
-                        &Table::default()
+                        Table::default()
                             .int("The Banana", -2)
                             .int("The Bob", -19)
                             .int("The Beta", -200),
                     )
                     .table(
-                        &Table::default()
+                        Table::default()
                             .int("The Cherry", -3)
                             .int("The Charley", -20)
                             .int("The Gamma", -300),
@@ -232,9 +232,9 @@ key",
         Table::default()
             .sub_aot(
                 "z1",
-                &ArrayOfTable::default()
+                ArrayOfTable::default()
                     .table(
-                        &Table::default().sub_t(
+                        Table::default().sub_t(
                             "a",
                             Table::default()
                                 .int("Apple", 1)
@@ -243,7 +243,7 @@ key",
                         ),
                     )
                     .table(
-                        &Table::default().sub_t(
+                        Table::default().sub_t(
                             "b",
                             Table::default()
                                 .int("Banana", 2)
@@ -252,7 +252,7 @@ key",
                         ),
                     )
                     .table(
-                        &Table::default().sub_t(
+                        Table::default().sub_t(
                             "c",
                             Table::default()
                                 .int("Cherry", 3)
@@ -263,9 +263,9 @@ key",
             )
             .sub_aot(
                 "z2",
-                &ArrayOfTable::default()
+                ArrayOfTable::default()
                     .table(
-                        &Table::default().sub_t(
+                        Table::default().sub_t(
                             "a",
                             Table::default()
                                 .int("The Apple", -1)
@@ -274,7 +274,7 @@ key",
                         ),
                     )
                     .table(
-                        &Table::default().sub_t(
+                        Table::default().sub_t(
                             "b",
                             Table::default()
                                 .int("The Banana", -2)
@@ -283,7 +283,7 @@ key",
                         ),
                     )
                     .table(
-                        &Table::default().sub_t(
+                        Table::default().sub_t(
                             "c",
                             Table::default()
                                 .int("The Cherry", -3)
@@ -0,0 +1,140 @@
+//! Compress rotated log files.  
+//! ローテーションされたログ・ファイルを圧縮します。  
+use crate::{Error, Log};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+
+/// Extension of the compressed file.  
+/// 圧縮ファイルの拡張子。  
+pub const COMPRESSED_EXTENSION: &str = ".gz";
+
+/// Added to the compressed file while it is written.  
+/// 書き込み中の圧縮ファイルに付けます。  
+const TEMP_EXTENSION: &str = ".tmp";
+
+/// Compress rotated log files.  
+/// ローテーションされたログ・ファイルを圧縮します。  
+pub struct Compressor {}
+impl Compressor {
+    /// Example: './default-2020-07-11.log.toml.gz'.  
+    pub fn compressed_path(path: &Path) -> PathBuf {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(COMPRESSED_EXTENSION);
+        PathBuf::from(name)
+    }
+
+    /// Compress the file in a separate thread, and remove the original.  
+    /// Nothing is printed. Errors are reported to `Log::last_error()`.  
+    /// 別スレッドでファイルを圧縮し、元のファイルを削除します。  
+    /// 何も表示しません。エラーは `Log::last_error()` に報告します。  
+    pub fn compress_in_background(path: PathBuf) {
+        thread::spawn(move || {
+            if let Err(error) = Compressor::compress_and_remove(&path) {
+                Log::report_error(error);
+            }
+        });
+    }
+
+    /// Compress the file, and remove the original.  
+    /// If compression fails, the original file remains and no compressed file is left.  
+    /// ファイルを圧縮し、元のファイルを削除します。  
+    /// 圧縮に失敗したら、元のファイルは残り、圧縮ファイルは残りません。  
+    fn compress_and_remove(path: &Path) -> Result<(), Error> {
+        let compressed = Compressor::compressed_path(path);
+        // A compressed file of the same name is not overwritten.
+        // 同じ名前の圧縮ファイルは上書きしません。
+        if compressed.exists() {
+            let err = io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists.", compressed.display()),
+            );
+            return Err(Error::compress(path, &err));
+        }
+        // Written under another name, so that a partial file never looks compressed.
+        // 書きかけのファイルが圧縮済みに見えないよう、別の名前で書きます。
+        let mut temp = compressed.clone().into_os_string();
+        temp.push(TEMP_EXTENSION);
+        let temp = PathBuf::from(temp);
+        if let Err(e) =
+            Compressor::compress(path, &temp).and_then(|_| fs::rename(&temp, &compressed))
+        {
+            let _ = fs::remove_file(&temp);
+            return Err(Error::compress(path, &e));
+        }
+        match fs::remove_file(path) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Error::remove(path, &e)),
+        }
+    }
+
+    fn compress(path: &Path, output: &Path) -> io::Result<()> {
+        let mut input = File::open(path)?;
+        let output = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(output)?;
+        let mut encoder = GzEncoder::new(output, Compression::default());
+        io::copy(&mut input, &mut encoder)?;
+        encoder.finish()?.sync_all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "casual_logger-compressor-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn compressed_file_has_the_same_text_and_the_original_is_removed() {
+        let dir = temp_dir("round-trip");
+        let path = dir.join("app-2020-07-11.log.toml");
+        let text =
+            "[\"Now=2020-07-11T15:30:00+0900&Pid=1&Thr=ThreadId(1)&Seq=1\"]\nInfo = '日本語'\n";
+        fs::write(&path, text).unwrap();
+
+        Compressor::compress_and_remove(&path).unwrap();
+        let mut decoded = String::new();
+        GzDecoder::new(File::open(Compressor::compressed_path(&path)).unwrap())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, text);
+        assert!(!path.exists());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn existing_compressed_file_is_not_overwritten() {
+        let dir = temp_dir("exists");
+        let path = dir.join("app-2020-07-11.log.toml");
+        fs::write(&path, "new").unwrap();
+        let compressed = Compressor::compressed_path(&path);
+        fs::write(&compressed, "old").unwrap();
+
+        assert!(matches!(
+            Compressor::compress_and_remove(&path),
+            Err(Error::Compress { .. })
+        ));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&compressed).unwrap(), "old");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,261 @@
+//! Watch the settings file, and reload it when changed.  
+//! 設定ファイルを見張り、変更されたら読み直します。  
+use crate::{Error, Level, Log, LogConfig, LogHandle, Table};
+use std::cell::Cell;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+// The handle of a watcher thread does not end the writer thread when dropped.
+// 見張りスレッドのハンドルは、破棄されても書き込みスレッドを終わらせません。
+thread_local!(static IS_WATCHER: Cell<bool> = const { Cell::new(false) });
+
+/// Watcher of the settings file of a logger.  
+/// ロガーの設定ファイルの見張り。  
+#[derive(Default)]
+pub struct Watcher {
+    /// Incremented at each watch or unwatch. An old watcher thread ends when it changes.  
+    /// 見張りの開始、終了のたびに増えます。変わると古い見張りスレッドは終わります。  
+    generation: Mutex<u64>,
+    /// Notified when the generation changes.  
+    /// 世代が変わると通知されます。  
+    changed: Condvar,
+}
+impl Watcher {
+    /// Start a new generation. The watcher thread of the old one ends.  
+    /// 新しい世代を始めます。古い世代の見張りスレッドは終わります。  
+    ///
+    /// # Returns
+    ///
+    /// The new generation.  
+    /// 新しい世代。  
+    pub fn stop(&self) -> u64 {
+        let mut generation = self
+            .generation
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *generation += 1;
+        self.changed.notify_all();
+        *generation
+    }
+
+    /// Wait for the interval.  
+    /// 間隔の分だけ待ちます。  
+    ///
+    /// # Returns
+    ///
+    /// True if the generation is still current.  
+    /// 世代がまだ今のものなら true 。  
+    fn wait(&self, generation: u64, interval: Duration) -> bool {
+        let current = self
+            .generation
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let (current, _) = self
+            .changed
+            .wait_timeout_while(current, interval, |current| *current == generation)
+            .unwrap_or_else(PoisonError::into_inner);
+        *current == generation
+    }
+}
+
+/// True on a watcher thread.  
+/// 見張りスレッドの上なら true 。  
+pub(crate) fn is_watcher() -> bool {
+    IS_WATCHER.try_with(|w| w.get()).unwrap_or(false)
+}
+
+/// Load the file into the logger, then watch it on a new thread.  
+/// ファイルをロガーへ読んでから、新しいスレッドで見張ります。  
+pub(crate) fn watch(log: &LogHandle, path: &str, interval_secs: u64) -> Result<(), Error> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => return Err(Error::open(Path::new(path), &e)),
+    };
+    log.apply_config(&LogConfig::from_toml(&text)?)?;
+
+    let generation = log.instance.watcher.stop();
+    // The thread does not keep the logger alive. Dropping its last handle ends the thread.
+    // スレッドはロガーを生かし続けません。最後のハンドルを破棄するとスレッドは終わります。
+    let instance = Arc::downgrade(&log.instance);
+    let path = path.to_string();
+    let interval = Duration::from_secs(interval_secs.max(1));
+    let spawned = thread::Builder::new()
+        .name("casual_logger-watch".to_string())
+        .spawn(move || {
+            IS_WATCHER.with(|w| w.set(true));
+            let mut last = Some(text);
+            while let Some(instance) = instance.upgrade() {
+                if !instance.watcher.wait(generation, interval) {
+                    break;
+                }
+                let current = fs::read_to_string(&path);
+                if current.as_ref().ok() == last.as_ref() {
+                    continue;
+                }
+                match current {
+                    Ok(text) => {
+                        reload(&LogHandle { instance }, &path, &text);
+                        last = Some(text);
+                    }
+                    Err(e) => {
+                        // Report once, until the file is readable again.
+                        // ファイルが読めるようになるまで、一度だけ報告します。
+                        Log::report_error(Error::open(Path::new(&path), &e));
+                        last = None;
+                    }
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        return Err(Error::Config(format!("Failed to watch. {}", e)));
+    }
+    Ok(())
+}
+
+/// Apply the changed file. If invalid, keep the current settings.  
+/// If the settings in effect changed, write a Notice log at any level.  
+/// 変更されたファイルを適用します。不正なら、今の設定のままにします。  
+/// 有効な設定が変わったら、どのレベルでも Notice ログを書きます。  
+///
+/// # Returns
+///
+/// True if the settings in effect changed.  
+/// 有効な設定が変わったら true 。  
+fn reload(log: &LogHandle, path: &str, text: &str) -> bool {
+    let old = match log.settings() {
+        Ok(old) => old,
+        Err(e) => {
+            Log::report_error(e);
+            return false;
+        }
+    };
+    if let Err(e) = LogConfig::from_toml(text).and_then(|config| log.apply_config(&config)) {
+        Log::report_error(e);
+        return false;
+    }
+    let new = match log.settings() {
+        Ok(new) => new,
+        Err(e) => {
+            Log::report_error(e);
+            return false;
+        }
+    };
+    if new == old {
+        // Only comments, or settings set with `_important`, changed.
+        // 変わったのはコメントか、 `_important` で設定されたものだけ。
+        return false;
+    }
+    let mut table = Table::default();
+    table.str("Path", path);
+    table.str("Level", &new.level.to_string());
+    table.str("OldLevel", &old.level.to_string());
+    log.reserve_table(
+        Level::Notice,
+        "casual_logger reloaded the configuration.",
+        false,
+        &mut table,
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_writes_a_record_only_when_the_settings_change() {
+        let dir = std::env::temp_dir().join(format!(
+            "casual_logger-config_watcher-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        let log = LogHandle::new();
+        log.set_dir(&dir.to_string_lossy());
+        log.set_level(Level::Error);
+
+        // Warn hides Notice, but the record is written.
+        assert!(reload(&log, "test.toml", "level = \"Warn\""));
+        // Only a comment changed.
+        assert!(!reload(&log, "test.toml", "# Quiet.\nlevel = \"Warn\""));
+        log.set_level_important(Level::Warn);
+        // An important setting is not changed.
+        assert!(!reload(&log, "test.toml", "level = \"Trace\""));
+        // Invalid.
+        assert!(!reload(&log, "test.toml", "level = \"Loud\""));
+        log.flush();
+
+        let mut text = String::new();
+        for entry in fs::read_dir(&dir).unwrap() {
+            text.push_str(&fs::read_to_string(entry.unwrap().path()).unwrap());
+        }
+        assert_eq!(text.matches("reloaded the configuration").count(), 1);
+        assert!(text.contains("OldLevel = 'Error'"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Wait until the condition holds, for up to 5 seconds.
+    fn wait_until<F: Fn() -> bool>(condition: F) -> bool {
+        for _ in 0..100 {
+            if condition() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        false
+    }
+
+    #[test]
+    fn each_logger_has_its_own_watcher() {
+        let dir = std::env::temp_dir().join(format!(
+            "casual_logger-config_watcher-each-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let watched = dir.join("watched.toml");
+        let unwatched = dir.join("unwatched.toml");
+        fs::write(&watched, "level = \"Warn\"").unwrap();
+        fs::write(&unwatched, "level = \"Warn\"").unwrap();
+        let a = LogHandle::new();
+        let b = LogHandle::new();
+        for log in &[&a, &b] {
+            log.set_dir(&dir.to_string_lossy());
+        }
+        a.watch_config(&watched.to_string_lossy(), 1).unwrap();
+        b.watch_config(&unwatched.to_string_lossy(), 1).unwrap();
+        // Unwatching one logger does not stop the other.
+        b.unwatch_config();
+        fs::write(&watched, "level = \"Debug\"").unwrap();
+        fs::write(&unwatched, "level = \"Debug\"").unwrap();
+
+        assert!(wait_until(|| a.get_level().ok() == Some(Level::Debug)));
+        thread::sleep(Duration::from_millis(1500));
+        assert_eq!(b.get_level().ok(), Some(Level::Warn));
+        drop(a);
+        drop(b);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unwatching_ends_the_thread_without_waiting_for_the_interval() {
+        let dir = std::env::temp_dir().join(format!(
+            "casual_logger-config_watcher-unwatch-{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("casual_logger.toml");
+        fs::write(&path, "level = \"Warn\"").unwrap();
+        let log = LogHandle::new();
+        log.watch_config(&path.to_string_lossy(), 3600).unwrap();
+        // The thread holds a weak reference until it ends.
+        assert_eq!(Arc::weak_count(&log.instance), 1);
+        log.unwatch_config();
+        assert!(wait_until(|| Arc::weak_count(&log.instance) == 0));
+        drop(log);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
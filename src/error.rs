@@ -0,0 +1,95 @@
+//! Errors of the logger.  
+//! ロガーのエラー。  
+use crate::Error;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, PoisonError};
+
+/// Error callback.  
+/// エラー・コールバック。  
+pub type ErrorCallback = Arc<dyn Fn(&Error) + Send + Sync>;
+
+impl Error {
+    pub(crate) fn open(path: &Path, err: &io::Error) -> Self {
+        Error::Open {
+            path: path.to_path_buf(),
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+    pub(crate) fn write(path: &Path, err: &io::Error) -> Self {
+        Error::Write {
+            path: path.to_path_buf(),
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+    pub(crate) fn read_dir(path: &Path, err: &io::Error) -> Self {
+        Error::ReadDir {
+            path: path.to_path_buf(),
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+    pub(crate) fn remove(path: &Path, err: &io::Error) -> Self {
+        Error::Remove {
+            path: path.to_path_buf(),
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+    pub(crate) fn compress(path: &Path, err: &io::Error) -> Self {
+        Error::Compress {
+            path: path.to_path_buf(),
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Open { path, message, .. } => {
+                write!(f, "Open failed. Path=|{}| {}", path.display(), message)
+            }
+            Error::Write { path, message, .. } => {
+                write!(f, "Write failed. Path=|{}| {}", path.display(), message)
+            }
+            Error::ReadDir { path, message, .. } => {
+                write!(f, "Read dir failed. Path=|{}| {}", path.display(), message)
+            }
+            Error::Remove { path, message, .. } => {
+                write!(f, "Remove failed. Path=|{}| {}", path.display(), message)
+            }
+            Error::Compress { path, message, .. } => {
+                write!(f, "Compress failed. Path=|{}| {}", path.display(), message)
+            }
+            Error::Pattern(message) => write!(f, "Bad file name pattern. {}", message),
+            Error::Poisoned(message) => write!(f, "Lock poisoned. {}", message),
+            Error::Config(message) => write!(f, "Invalid configuration. {}", message),
+            Error::AtExit(result) => write!(f, "Register at exit failed. Return=|{}|", result),
+        }
+    }
+}
+impl std::error::Error for Error {}
+impl<T> From<PoisonError<T>> for Error {
+    fn from(err: PoisonError<T>) -> Self {
+        Error::Poisoned(err.to_string())
+    }
+}
+
+/// Errors that occurred in the logger.  
+/// ロガーで起きたエラー。  
+#[derive(Default)]
+pub struct ErrorState {
+    /// Last error.  
+    /// 最後のエラー。  
+    pub last: Option<Error>,
+    /// Number of errors.  
+    /// エラーの数。  
+    pub count: u64,
+    /// Called each time an error occurs.  
+    /// エラーが起きるたびに呼ばれます。  
+    pub callback: Option<ErrorCallback>,
+}
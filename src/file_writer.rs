@@ -0,0 +1,396 @@
+//! Write the log to the file.  
+//! ログをファイルに書きます。  
+use crate::compressor::Compressor;
+use crate::log_file::LogFile;
+use crate::logger::Logger;
+use crate::{Error, Fallback};
+use chrono::Local;
+use std::collections::VecDeque;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// First wait time to retry opening the file.  
+/// ファイルを開き直すまでの最初の待ち時間。  
+const RETRY_WAIT_MIN_MILLIS: u64 = 100;
+/// Longest wait time to retry opening the file.  
+/// ファイルを開き直すまでの最長の待ち時間。  
+const RETRY_WAIT_MAX_MILLIS: u64 = 30_000;
+
+/// Write the log to the file.  
+/// The settings are given at each write, so the settings are not locked during file I/O.  
+/// ログをファイルに書きます。  
+/// 設定は書き込みのたびに渡すので、ファイル入出力の間は設定をロックしません。  
+pub struct FileWriter {
+    /// Controll file.  
+    log_file: Option<LogFile>,
+    /// Logs waiting for the file to be writable. `Fallback::Memory`.  
+    /// ファイルが書けるようになるのを待っているログ。 `Fallback::Memory`。  
+    pending: VecDeque<Vec<u8>>,
+    /// Total bytes of pending logs.  
+    /// 待っているログの合計バイト数。  
+    pending_len: usize,
+    /// Do not open the file until this time, after a failure.  
+    /// 失敗の後、この時刻まではファイルを開きません。  
+    retry_at: Option<Instant>,
+    /// Wait time until the next retry. It doubles with each failure.  
+    /// 次の再試行までの待ち時間。失敗するたびに倍になります。  
+    retry_wait: Duration,
+}
+impl Default for FileWriter {
+    fn default() -> Self {
+        FileWriter {
+            log_file: None,
+            pending: VecDeque::new(),
+            pending_len: 0,
+            retry_at: None,
+            retry_wait: Duration::from_millis(RETRY_WAIT_MIN_MILLIS),
+        }
+    }
+}
+impl FileWriter {
+    /// Path of the file being written. None if it is not opened.  
+    /// 書き込み中のファイルのパス。開いていなければ None 。  
+    pub fn current_path(&self) -> Option<&Path> {
+        self.log_file
+            .as_ref()
+            .map(|log_file| log_file.path.as_path())
+    }
+
+    /// Close the file. It will be reopened at the next write.  
+    /// ファイルを閉じます。次の書き込みで開き直します。  
+    pub fn close_file(&mut self) {
+        self.log_file = None;
+        // The new file can be tried at once.
+        // 新しいファイルはすぐに試せます。
+        self.retry_at = None;
+    }
+
+    /// Write to the current file.  
+    /// If the file cannot be written, the fallback is used,  
+    /// and the file is retried later with backoff.  
+    /// 現在のファイルに書き込みます。  
+    /// ファイルに書けなければ代替手段を使い、  
+    /// 間隔を空けながら後でファイルを再試行します。  
+    ///
+    /// # Returns
+    ///
+    /// Error, if the file could not be written this time.  
+    /// 今回ファイルに書けなかったらエラー。  
+    pub fn write(&mut self, logger: &Logger, text: &str) -> Result<(), Error> {
+        if let Some(retry_at) = self.retry_at {
+            if Instant::now() < retry_at {
+                // Waiting for retry. The error has already been reported.
+                // 再試行待ち。エラーは報告済みです。
+                self.fall_back(logger.fallback, text.as_bytes());
+                return Ok(());
+            }
+        }
+
+        // Pending logs first, to keep the order.
+        // 順序を保つため、待っているログが先です。
+        let mut buf = Vec::with_capacity(self.pending_len + text.len());
+        for pending in &self.pending {
+            buf.extend_from_slice(pending);
+        }
+        buf.extend_from_slice(text.as_bytes());
+
+        let (written, result) = self.write_file(logger, &buf);
+        // The bytes already written are not written again.
+        // 書けたバイトは、もう一度は書きません。
+        let pending_len = self.pending_len;
+        self.consume_pending(std::cmp::min(written, pending_len));
+        let rest = &text.as_bytes()[written.saturating_sub(pending_len)..];
+        match result {
+            Ok(_) => {
+                self.retry_at = None;
+                self.retry_wait = Duration::from_millis(RETRY_WAIT_MIN_MILLIS);
+                Ok(())
+            }
+            Err(error) => {
+                // Open again at the retry.
+                // 再試行で開き直します。
+                self.log_file = None;
+                self.retry_at = Some(Instant::now() + self.retry_wait);
+                self.retry_wait = std::cmp::min(
+                    self.retry_wait * 2,
+                    Duration::from_millis(RETRY_WAIT_MAX_MILLIS),
+                );
+                self.fall_back(logger.fallback, rest);
+                Err(error)
+            }
+        }
+    }
+
+    /// Synchronize the current file to the disk.  
+    /// 現在のファイルをディスクへ同期します。  
+    pub fn sync(&mut self) -> Result<(), Error> {
+        if let Some(log_file) = &self.log_file {
+            if let Err(e) = log_file.file.sync_data() {
+                return Err(Error::write(&log_file.path, &e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Keep or output the log that could not be written to the file.  
+    /// ファイルに書けなかったログを、取っておくか出力します。  
+    fn fall_back(&mut self, fallback: Fallback, text: &[u8]) {
+        if text.is_empty() {
+            return;
+        }
+        match fallback {
+            Fallback::Discard => {}
+            Fallback::Stderr => {
+                let _ = io::stderr().write_all(text);
+            }
+            Fallback::Memory(capacity) => {
+                self.pending.push_back(text.to_vec());
+                self.pending_len += text.len();
+                // Discard the oldest logs, if it is over the capacity.
+                // 容量を超えたら、古いログから捨てます。
+                while capacity < self.pending_len {
+                    if let Some(oldest) = self.pending.pop_front() {
+                        self.pending_len -= oldest.len();
+                    } else {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove the bytes already written from the front of the pending logs.  
+    /// 書けたバイトを、待っているログの先頭から取り除きます。  
+    fn consume_pending(&mut self, mut written: usize) {
+        while 0 < written {
+            let front_len = match self.pending.front() {
+                Some(front) => front.len(),
+                None => break,
+            };
+            if front_len <= written {
+                self.pending.pop_front();
+                self.pending_len -= front_len;
+                written -= front_len;
+            } else {
+                if let Some(front) = self.pending.front_mut() {
+                    front.drain(..written);
+                }
+                self.pending_len -= written;
+                written = 0;
+            }
+        }
+    }
+
+    /// Write the bytes to the current file.  
+    /// バイト列を現在のファイルに書きます。  
+    ///
+    /// # Returns
+    ///
+    /// Number of bytes written, even if it failed on the way.  
+    /// 書けたバイト数。途中で失敗しても返します。  
+    fn write_file(&mut self, logger: &Logger, bytes: &[u8]) -> (usize, Result<(), Error>) {
+        let log_file = match self.current_file(logger) {
+            Ok(log_file) => log_file,
+            Err(error) => return (0, Err(error)),
+        };
+        let mut file = &log_file.file;
+        let mut written = 0;
+        while written < bytes.len() {
+            match file.write(&bytes[written..]) {
+                Ok(0) => {
+                    let e = io::Error::from(io::ErrorKind::WriteZero);
+                    return (written, Err(Error::write(&log_file.path, &e)));
+                }
+                Ok(n) => written += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return (written, Err(Error::write(&log_file.path, &e))),
+            }
+        }
+        (written, Ok(()))
+    }
+
+    /// Get file, or rotation file.  
+    /// The file is opened, if it is not opened.  
+    /// ファイル、またはローテーションしたファイルを取得します。  
+    /// 開いていなければ開きます。  
+    fn current_file(&mut self, logger: &Logger) -> Result<&LogFile, Error> {
+        // Check period, and size.
+        let mut next_part = 0;
+        if let Some(log_file) = self.log_file.take() {
+            if log_file.start != logger.rotation.start_of(Local::now()) {
+                FileWriter::rotate(logger, log_file);
+            } else if FileWriter::is_over_size(logger, &log_file.path) {
+                // Next part in the same period.
+                next_part = log_file.part + 1;
+                FileWriter::rotate(logger, log_file);
+            } else {
+                return Ok(self.log_file.insert(log_file));
+            }
+        }
+
+        // New file, if period changed, file is full, or not opened yet.
+        let log_file = FileWriter::new_period_file(logger, next_part)?;
+        if logger.auto_remove {
+            // The new file is kept. Writing does not wait for the removal.
+            // 新しいファイルは残ります。書き込みは削除を待ちません。
+            let mut retention = logger.retention();
+            retention.current = Some(log_file.path.clone());
+            retention.remove_in_background();
+        }
+        Ok(self.log_file.insert(log_file))
+    }
+
+    /// Close the file of the ended period or the full file, and compress it.  
+    /// 終わった周期のファイルか、一杯のファイルを閉じて圧縮します。  
+    fn rotate(logger: &Logger, log_file: LogFile) {
+        // Close before compression.
+        // 圧縮の前に閉じます。
+        let LogFile { path, file, .. } = log_file;
+        drop(file);
+        if logger.compress {
+            Compressor::compress_in_background(path);
+        }
+    }
+
+    /// Is the file size over the limit?  
+    /// ファイル・サイズが上限を超えていますか？  
+    fn is_over_size(logger: &Logger, path: &Path) -> bool {
+        if logger.max_file_size < 1 {
+            // Unlimited.
+            return false;
+        }
+        if let Ok(metadata) = fs::metadata(path) {
+            logger.max_file_size <= metadata.len()
+        } else {
+            // Not exists.
+            false
+        }
+    }
+
+    /// Is the part already used? It is full, or compressed.  
+    /// その部は使用済みですか？ 一杯か、圧縮済みです。  
+    fn is_used_part(logger: &Logger, path: &Path) -> bool {
+        FileWriter::is_over_size(logger, path) || Compressor::compressed_path(path).exists()
+    }
+
+    /// Create new file, or get exists file.  
+    ///
+    /// # Arguments
+    ///
+    /// * `first_part` - Part number to start searching from.  
+    ///   探し始める部番号。  
+    fn new_period_file(logger: &Logger, first_part: u32) -> Result<LogFile, Error> {
+        let start = logger.rotation.start_of(Local::now());
+        // The directory may have been removed after it was set.
+        // 設定後にディレクトリーが削除されているかもしれません。
+        let _ = fs::create_dir_all(&logger.dir);
+        // Skip the parts that are already full or compressed.
+        // 既に一杯か、圧縮済みの部は飛ばします。
+        let mut part = first_part;
+        let mut path = logger.file_path(&start, part);
+        while FileWriter::is_used_part(logger, &path) {
+            part += 1;
+            path = logger.file_path(&start, part);
+        }
+        match OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => Ok(LogFile::new(start, part, path, file)),
+            Err(e) => Err(Error::open(&path, &e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compressor::COMPRESSED_EXTENSION;
+    use std::path::PathBuf;
+    use std::thread;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "casual_logger-file_writer-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rotation_after_set_dir_compresses_the_opened_file() {
+        let old_dir = temp_dir("old-dir");
+        let new_dir = temp_dir("new-dir");
+        let mut file_writer = FileWriter::default();
+        let mut logger = Logger {
+            dir: old_dir.to_string_lossy().to_string(),
+            file_prefix: "before".to_string(),
+            max_file_size: 1,
+            compress: true,
+            auto_remove: false,
+            ..Logger::default()
+        };
+        file_writer.write(&logger, "first\n").unwrap();
+
+        logger.dir = new_dir.to_string_lossy().to_string();
+        logger.file_prefix = "after".to_string();
+        file_writer.write(&logger, "second\n").unwrap();
+
+        // The file opened before the change is the one that is compressed.
+        let mut names = file_names(&old_dir);
+        for _ in 0..100 {
+            if names.len() == 1 && names[0].ends_with(COMPRESSED_EXTENSION) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(50));
+            names = file_names(&old_dir);
+        }
+        assert_eq!(names.len(), 1, "{:?}", names);
+        assert!(names[0].starts_with("before-"), "{:?}", names);
+        assert!(names[0].ends_with(COMPRESSED_EXTENSION), "{:?}", names);
+
+        let names = file_names(&new_dir);
+        assert_eq!(names.len(), 1, "{:?}", names);
+        assert!(names[0].starts_with("after-"), "{:?}", names);
+
+        let _ = fs::remove_dir_all(&old_dir);
+        let _ = fs::remove_dir_all(&new_dir);
+    }
+
+    #[test]
+    fn consume_pending_skips_the_bytes_already_written() {
+        let mut file_writer = FileWriter::default();
+        let fallback = Fallback::Memory(1024);
+        file_writer.fall_back(fallback, b"ab\n");
+        file_writer.fall_back(fallback, b"cd\n");
+        file_writer.fall_back(fallback, b"ef\n");
+
+        // Failed in the middle of the second record.
+        file_writer.consume_pending(4);
+        assert_eq!(file_writer.pending, vec![b"d\n".to_vec(), b"ef\n".to_vec()]);
+        assert_eq!(file_writer.pending_len, 5);
+
+        file_writer.consume_pending(2);
+        assert_eq!(file_writer.pending, vec![b"ef\n".to_vec()]);
+        assert_eq!(file_writer.pending_len, 3);
+
+        file_writer.consume_pending(0);
+        assert_eq!(file_writer.pending_len, 3);
+        file_writer.consume_pending(10);
+        assert!(file_writer.pending.is_empty());
+        assert_eq!(file_writer.pending_len, 0);
+    }
+}
@@ -0,0 +1,44 @@
+//! Flush at the end of the scope.  
+//! スコープの終わりにフラッシュします。  
+use crate::{Error, FlushGuard, Log};
+use std::os::raw::c_int;
+use std::sync::Once;
+
+extern "C" {
+    /// C standard library.
+    fn atexit(callback: extern "C" fn()) -> c_int;
+}
+
+static REGISTER_AT_EXIT: Once = Once::new();
+
+impl FlushGuard {
+    pub(crate) fn new() -> Self {
+        FlushGuard {}
+    }
+
+    /// Register the flush to run when the process exits. Only once.  
+    /// プロセスの終了時にフラッシュが走るよう登録します。一度だけ。  
+    pub fn register_at_exit() {
+        REGISTER_AT_EXIT.call_once(|| {
+            // SAFETY: `flush_at_exit` is an `extern "C" fn()` with no arguments,
+            // as `atexit` requires. It is a static function, so it is still valid
+            // when the process exits, and it does not unwind into C.
+            let result = unsafe { atexit(FlushGuard::flush_at_exit) };
+            if result != 0 {
+                // The flush is not performed at exit.
+                // 終了時のフラッシュは行われません。
+                Log::report_error(Error::AtExit(result));
+            }
+        });
+    }
+
+    extern "C" fn flush_at_exit() {
+        // Do not unwind into C.
+        let _ = std::panic::catch_unwind(Log::flush);
+    }
+}
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        Log::flush();
+    }
+}
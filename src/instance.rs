@@ -0,0 +1,36 @@
+//! One logger. Its settings, file and writer thread.  
+//! 一つのロガー。その設定、ファイル、書き込みスレッド。  
+use crate::config_watcher::Watcher;
+use crate::file_writer::FileWriter;
+use crate::logger::Logger;
+use crate::writer::Writer;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// One logger. Its settings, file and writer thread.  
+/// 一つのロガー。その設定、ファイル、書き込みスレッド。  
+#[derive(Default)]
+pub struct Instance {
+    /// Settings. It is not locked during file I/O.  
+    /// 設定。ファイル入出力の間はロックしません。  
+    pub logger: Mutex<Logger>,
+    /// The file. Only the writing thread locks it for long.  
+    /// ファイル。長くロックするのは書き込むスレッドだけです。  
+    pub file: Mutex<FileWriter>,
+    /// Queue of the writer thread.  
+    /// 書き込みスレッドのキュー。  
+    pub writer: Writer,
+    /// Watcher of the settings file.  
+    /// 設定ファイルの見張り。  
+    pub watcher: Watcher,
+    /// The last sequential number of the tables.  
+    /// テーブルの最後の連番。  
+    seq: AtomicU64,
+}
+impl Instance {
+    /// Automatic sequential number of this logger, from 1.  
+    /// このロガーの自動連番。 1 から。  
+    pub fn create_seq(&self) -> u128 {
+        u128::from(self.seq.fetch_add(1, Ordering::Relaxed)) + 1
+    }
+}
@@ -0,0 +1,149 @@
+//! Levels per module or target.  
+//! モジュールまたはターゲットごとのレベル。  
+use crate::{Error, Level, LevelDirectives};
+use std::fmt;
+use std::str::FromStr;
+
+impl LevelDirectives {
+    /// No directives.  
+    /// 指定無し。  
+    pub fn new() -> Self {
+        LevelDirectives::default()
+    }
+
+    /// There are no directives.  
+    /// 指定がありません。  
+    pub fn is_empty(&self) -> bool {
+        self.directives.is_empty()
+    }
+
+    /// The most verbose level number of the directives. 0 if none.  
+    /// 指定の中で一番詳しいレベルの番号。無ければ 0 。  
+    pub(crate) fn max_number(&self) -> usize {
+        self.max_number
+    }
+
+    /// The level of the longest directive that matches the module or target.  
+    /// "a::b" matches "a::b" and "a::b::c", but not "a::bc".  
+    /// モジュールまたはターゲットに一致する、一番長い指定のレベル。  
+    /// "a::b" は "a::b" と "a::b::c" に一致し、 "a::bc" には一致しません。  
+    pub(crate) fn level_for(&self, target: &str) -> Option<Level> {
+        for (module, level) in &self.directives {
+            if target.starts_with(module.as_str())
+                && (target.len() == module.len() || target[module.len()..].starts_with("::"))
+            {
+                return Some(*level);
+            }
+        }
+        None
+    }
+}
+impl FromStr for LevelDirectives {
+    type Err = Error;
+    /// Comma separated "module=Level". A module written twice uses the last one.  
+    /// カンマ区切りの "module=Level" 。二度書かれたモジュールは最後のものを使います。  
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut directives: Vec<(String, Level)> = Vec::new();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let (module, level) = match directive.find('=') {
+                Some(index) => (directive[..index].trim(), directive[index + 1..].trim()),
+                None => ("", ""),
+            };
+            if module.is_empty() {
+                return Err(Error::Config(format!(
+                    "A level directive must be module=Level. Directive=|{}|",
+                    directive
+                )));
+            }
+            let level: Level = level.parse()?;
+            directives.retain(|(m, _)| m != module);
+            directives.push((module.to_string(), level));
+        }
+        // Longest first, so that the first match is the longest.
+        // 最初に一致したものが一番長くなるよう、長い順にします。
+        directives.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        let max_number = directives
+            .iter()
+            .map(|(_, level)| level.number())
+            .max()
+            .unwrap_or(0);
+        Ok(LevelDirectives {
+            directives,
+            max_number,
+        })
+    }
+}
+impl fmt::Display for LevelDirectives {
+    /// Same as the text parsed, such as "mygame::ai=Trace,mygame::net=Warn".  
+    /// "mygame::ai=Trace,mygame::net=Warn" など、パースするテキストと同じです。  
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text: Vec<String> = self
+            .directives
+            .iter()
+            .map(|(module, level)| format!("{}={}", module, level))
+            .collect();
+        write!(f, "{}", text.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logger::Logger;
+
+    #[test]
+    fn parse_trims_skips_empty_and_keeps_the_last_duplicate() {
+        let directives: LevelDirectives = " a=Info, ,a::b = Warn,a=Debug,".parse().unwrap();
+        assert_eq!(directives.to_string(), "a::b=Warn,a=Debug");
+        assert_eq!(directives.max_number(), Level::Debug.number());
+        assert!("".parse::<LevelDirectives>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_a_missing_module_or_an_unknown_level() {
+        assert!("a".parse::<LevelDirectives>().is_err());
+        assert!("=Info".parse::<LevelDirectives>().is_err());
+        assert!("a=Loud".parse::<LevelDirectives>().is_err());
+    }
+
+    #[test]
+    fn the_longest_module_on_a_path_boundary_matches() {
+        let directives: LevelDirectives = "a=Error,a::b=Trace,a::b::c=Warn".parse().unwrap();
+        assert_eq!(
+            directives.level_for("a").map(|level| level.number()),
+            Some(Level::Error.number())
+        );
+        assert_eq!(
+            directives.level_for("a::b").map(|level| level.number()),
+            Some(Level::Trace.number())
+        );
+        assert_eq!(
+            directives.level_for("a::b::d").map(|level| level.number()),
+            Some(Level::Trace.number())
+        );
+        assert_eq!(
+            directives
+                .level_for("a::b::c::e")
+                .map(|level| level.number()),
+            Some(Level::Warn.number())
+        );
+        assert_eq!(
+            directives.level_for("a::bc").map(|level| level.number()),
+            Some(Level::Error.number())
+        );
+        assert!(directives.level_for("ab").is_none());
+    }
+
+    #[test]
+    fn a_target_without_a_directive_uses_the_level() {
+        let logger = Logger {
+            level: Level::Info,
+            level_directives: "a=Trace,b=Error".parse().unwrap(),
+            ..Logger::default()
+        };
+        assert!(logger.enabled_for(Level::Trace, "a::x"));
+        assert!(!logger.enabled_for(Level::Warn, "b"));
+        assert!(logger.enabled_for(Level::Info, "c"));
+        assert!(!logger.enabled_for(Level::Debug, "c"));
+    }
+}
@@ -28,35 +28,51 @@
 #[macro_use]
 extern crate lazy_static;
 extern crate chrono;
+extern crate flate2;
 extern crate regex;
 // extern crate sys_info;
 
+mod compressor;
+mod config_watcher;
+mod error;
+mod file_writer;
+mod flush_guard;
+mod instance;
+mod level_directives;
+mod log_config;
+#[cfg(feature = "log")]
+mod log_facade;
 mod log_file;
+mod log_handle;
 mod logger;
+mod macros;
+mod retention;
+mod rotation;
 mod stringifier;
 mod table;
+mod table_value;
 mod toml;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+mod writer;
 
-use crate::logger::Logger;
-use crate::logger::LOGGER;
-use crate::stringifier::Stringifier;
+use crate::error::{ErrorCallback, ErrorState};
 use crate::table::InternalTable;
-use std::cell::RefCell;
 use std::collections::BTreeMap;
-use std::collections::VecDeque;
 use std::fmt;
-use std::io::{BufWriter, Write};
-use std::sync::Mutex;
-use std::thread;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 // use sys_info::mem_info;
 
 // For multi-platform. Windows, or not.
 #[cfg(windows)]
-const NEW_LINE: &'static str = "\r\n";
+const NEW_LINE: &str = "\r\n";
 // #[cfg(windows)]
 // const NEW_LINE_ESCAPED_CHARS: &'static [char; 4] = &['\\', 'r', '\\', 'n'];
 #[cfg(not(windows))]
-const NEW_LINE: &'static str = "\n";
+const NEW_LINE: &str = "\n";
 // #[cfg(not(windows))]
 // const NEW_LINE_ESCAPED_CHARS: &'static [char; 2] = &['\\', 'n'];
 
@@ -68,12 +84,90 @@ pub const DEFAULT_LOG_LEVEL: Level = Level::Trace;
 /// ファイルの保存期間は、デフォルトで7日です。  
 pub const DEFAULT_RETENTION_DAYS: i64 = 7;
 
+/// The number of files is unlimited by default.  
+/// ファイル数はデフォルトで無制限です。  
+pub const DEFAULT_RETENTION_FILES: usize = 0;
+
+/// The total size of files is unlimited by default.  
+/// ファイルの合計サイズはデフォルトで無制限です。  
+pub const DEFAULT_RETENTION_SIZE: u64 = 0;
+
 /// The default timeout is 30 seconds.  
 /// Used for Log::flush() wait time.  
 /// タイムアウトのデフォルトは30秒です。  
 /// Log::flush() の待機時間に使われます。  
 pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
+/// Log files are placed in the working directory by default.  
+/// ログ・ファイルはデフォルトで作業ディレクトリーに置かれます。  
+pub const DEFAULT_DIR: &str = ".";
+
+/// The file size is unlimited by default.  
+/// ファイル・サイズはデフォルトで無制限です。  
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 0;
+
+/// Rotated files are not compressed by default.  
+/// ローテーションされたファイルはデフォルトで圧縮しません。  
+pub const DEFAULT_COMPRESS: bool = false;
+
+/// Old log files are not removed automatically by default.  
+/// 古いログ・ファイルはデフォルトで自動削除しません。  
+pub const DEFAULT_AUTO_REMOVE: bool = false;
+
+/// While the file cannot be written, up to 1 MiB of logs are kept in memory by default.  
+/// ファイルに書けない間、デフォルトで 1 MiB までのログをメモリーに取っておきます。  
+pub const DEFAULT_FALLBACK: Fallback = Fallback::Memory(1024 * 1024);
+
+/// The queue of logs waiting to be written is unlimited by default.  
+/// 書き込みを待つログのキューはデフォルトで無制限です。  
+pub const DEFAULT_QUEUE_CAPACITY: usize = 0;
+
+/// When the queue is full, the caller waits by default.  
+/// キューが満杯のとき、デフォルトで呼び出し元が待ちます。  
+pub const DEFAULT_OVERFLOW: Overflow = Overflow::Block;
+
+/// The file is not synchronized to the disk by level by default.  
+/// デフォルトでは、レベルによってファイルをディスクへ同期しません。  
+pub const DEFAULT_SYNC_LEVEL: Option<Level> = None;
+
+/// Logs are written in the background by default.  
+/// ログはデフォルトでバックグラウンドで書き込みます。  
+pub const DEFAULT_WRITE_MODE: WriteMode = WriteMode::Background;
+
+/// No module has its own level by default.  
+/// デフォルトでは、どのモジュールも自分のレベルを持ちません。  
+pub const DEFAULT_LEVEL_DIRECTIVES: LevelDirectives = LevelDirectives {
+    directives: Vec::new(),
+    max_number: 0,
+};
+
+/// Environment variable for the log level of `Log::`, such as "Info".  
+/// The environment variables are read at the first use of `Log::`,  
+/// and set as `Log::set_xxx_important()`.  
+/// So they win over `Log::set_xxx()` and `Log::set_xxx_important()` in the code.  
+/// An empty or invalid value is ignored, and recorded in `Log::last_error()`.  
+/// `Log::` のログ・レベルの環境変数。 "Info" など。  
+/// 環境変数は `Log::` を最初に使うときに読み、 `Log::set_xxx_important()` として設定します。  
+/// そのため、コードの `Log::set_xxx()` と `Log::set_xxx_important()` に勝ちます。  
+/// 空や不正な値は無視し、 `Log::last_error()` に記録します。  
+pub const ENV_LEVEL: &str = "CASUAL_LOGGER_LEVEL";
+
+/// Environment variable for the optimization of `Log::`, such as "Release". See also: `ENV_LEVEL`.  
+/// `Log::` の最適化の環境変数。 "Release" など。  
+pub const ENV_OPT: &str = "CASUAL_LOGGER_OPT";
+
+/// Environment variable for the directory of `Log::`. See also: `ENV_LEVEL`.  
+/// `Log::` のディレクトリーの環境変数。  
+pub const ENV_DIR: &str = "CASUAL_LOGGER_DIR";
+
+/// Environment variable for the retention days of `Log::`. See also: `ENV_LEVEL`.  
+/// `Log::` の保持日数の環境変数。  
+pub const ENV_RETENTION_DAYS: &str = "CASUAL_LOGGER_RETENTION_DAYS";
+
+/// Environment variable for the level directives of `Log::`, such as "mygame::ai=Trace". See also: `ENV_LEVEL`.  
+/// `Log::` のレベル指定の環境変数。 "mygame::ai=Trace" など。  
+pub const ENV_LEVEL_DIRECTIVES: &str = "CASUAL_LOGGER_LEVEL_DIRECTIVES";
+
 /// The optimization is `Opt::BeginnersSupport` by default.  
 /// 最適化はデフォルトで `Opt::BeginnersSupport` です。  
 pub const DEFAULT_OPTIMIZATION: Opt = Opt::BeginnersSupport;
@@ -84,7 +178,7 @@ pub const DEFAULT_OPTIMIZATION: Opt = Opt::BeginnersSupport;
 /// |<-- Low Level ------------------------- High level -->|  
 /// |<-- High priority ------------------- Low priority -->|  
 /// | Fatal < Error < Warn < Notice < Info < Debug < Trace |  
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Level {
     /// If the program cannot continue.  
     Fatal,
@@ -125,6 +219,23 @@ impl Level {
         }
     }
 }
+impl FromStr for Level {
+    type Err = Error;
+    /// Same as the Display, such as "Info".  
+    /// Display と同じで、 "Info" など。  
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Fatal" => Ok(Level::Fatal),
+            "Error" => Ok(Level::Error),
+            "Warn" => Ok(Level::Warn),
+            "Notice" => Ok(Level::Notice),
+            "Info" => Ok(Level::Info),
+            "Debug" => Ok(Level::Debug),
+            "Trace" => Ok(Level::Trace),
+            _ => Err(Error::Config(format!("Unknown level. Level=|{}|", s))),
+        }
+    }
+}
 impl fmt::Display for Level {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -152,29 +263,14 @@ impl fmt::Display for Level {
 // * References
 //      * [How can I use mutable lazy_static?](https://users.rust-lang.org/t/how-can-i-use-mutable-lazy-static/3751/3)
 lazy_static! {
-    /// Table buffer.
-    static ref QUEUE_T: Mutex<VecDeque<InternalTable>> = Mutex::new(VecDeque::<InternalTable>::new());
-    static ref QUEUE_F: Mutex<VecDeque<InternalTable>> = Mutex::new(VecDeque::<InternalTable>::new());
-    static ref RESERVE_TARGET: Mutex<ReserveTarget> = Mutex::new(ReserveTarget::default());
-    static ref SIGNAL_CAN_FLUSH: Mutex<SignalCanFlush> = Mutex::new(SignalCanFlush::default());
-    // Wait for logging to complete.
-    //static ref PARTICIPANTING_THREADS_COUNTER: Mutex<ParticipatingThreadsCounter> = Mutex::new(ParticipatingThreadsCounter::default());
-    /// Optimization.
-    static ref OPT_STATE: Mutex<OptState> = Mutex::new(OptState::default());
+    /// Errors.
+    static ref ERROR_STATE: Mutex<ErrorState> = Mutex::new(ErrorState::default());
+    /// The logger behind `Log::`.
+    static ref DEFAULT_LOG: LogHandle = LogHandle::from_env();
 }
-// Use the line number in the log.
-//
-// Thread local scope variable.
-//
-// * References
-//      * [ミュータブルなスレッドローカルデータを thread_local!() マクロと RefCell で実現する](https://qiita.com/tatsuya6502/items/bed3702517b36afbdbca)
-thread_local!(static SEQ: RefCell<u128> = {
-    RefCell::new(1)
-});
-
 /// Array of Table.  
 /// テーブルの配列。  
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct ArrayOfTable {
     tables: Vec<Table>,
 }
@@ -203,13 +299,13 @@ impl Table {
     /// # Arguments
     ///
     /// * `level` - Log level.  
-    ///             ログ・レベル。  
+    ///   ログ・レベル。  
     /// * `trailing_newline` - Trailing newline.  
-    ///                         改行の有無。  
+    ///   改行の有無。  
     fn new(level: Level, message: &str, trailing_newline: bool, base_name: &str) -> Self {
         Table {
             base_name: base_name.to_string(),
-            level: level,
+            level,
             message: message.to_string(),
             message_trailing_newline: trailing_newline,
             sorted_map: None,
@@ -221,7 +317,7 @@ impl Table {
     where
         F: FnMut(&mut BTreeMap<String, String>),
     {
-        if let None = self.sorted_map {
+        if self.sorted_map.is_none() {
             self.sorted_map = Some(BTreeMap::new());
         }
 
@@ -234,7 +330,7 @@ impl Table {
     where
         F: FnMut(&mut BTreeMap<String, InternalTable>),
     {
-        if let None = self.sub_tables {
+        if self.sub_tables.is_none() {
             self.sub_tables = Some(BTreeMap::new());
         }
 
@@ -247,6 +343,34 @@ impl Table {
 /// Easy to use logging.  
 /// 使いやすいロギング。  
 pub struct Log {}
+
+/// An independent logger, with its own file, settings and writer thread.  
+/// It has the same methods as `Log::`, such as `set_file_name()` and `info()`.  
+/// `Log::` is this kind of logger, the default one.  
+/// When dropped, what is left is written.  
+/// The errors are shared with `Log::`.  
+/// The keys of a table are corrected by the optimization of `Log::`,  
+/// because a table is made before it is given to a logger.  
+/// 独自のファイル、設定、書き込みスレッドを持つ、独立したロガーです。  
+/// `set_file_name()` や `info()` など、 `Log::` と同じメソッドを持ちます。  
+/// `Log::` はこの種類のロガーの、デフォルトのものです。  
+/// ドロップされると、残りを書き込みます。  
+/// エラーは `Log::` と共有します。  
+/// テーブルはロガーに渡す前に作るので、テーブルのキーは `Log::` の最適化で補正します。  
+///
+/// # Examples
+///
+/// ```no_run
+/// use casual_logger::{Level, LogHandle};
+///
+/// let ai = LogHandle::new();
+/// ai.set_file_name("ai");
+/// ai.set_level(Level::Debug);
+/// ai.debug("Thinking...");
+/// ```
+pub struct LogHandle {
+    instance: Arc<instance::Instance>,
+}
 impl Log {
     /// Set the log file name prefix.  
     /// ログ・ファイル名接頭辞を設定します。  
@@ -273,21 +397,14 @@ impl Log {
     ///
     /// See also: 'Log::set_file_name_important()'.  
     pub fn set_file_name(prefix: &str) {
-        if let Ok(mut logger) = LOGGER.lock() {
-            if !logger.file_name_important {
-                logger.file_prefix = prefix.to_string();
-            }
-        }
+        DEFAULT_LOG.set_file_name(prefix);
     }
 
     /// Example:  
     ///
     /// If 'tic-tac-toe-2020-07-11.log.toml', This is 'tic-tac-toe'.  
-    pub fn get_file_name() -> Result<String, String> {
-        match LOGGER.lock() {
-            Ok(logger) => Ok(logger.file_prefix.to_string()),
-            Err(e) => Err(e.to_string()),
-        }
+    pub fn get_file_name() -> Result<String, Error> {
+        DEFAULT_LOG.get_file_name()
     }
 
     /// For application use. No for library use.  
@@ -318,28 +435,14 @@ impl Log {
     ///
     /// See also: 'Log::set_file_name()'.  
     pub fn set_file_name_important(prefix: &str) {
-        Log::set_file_name(prefix);
-        if let Ok(mut logger) = LOGGER.lock() {
-            logger.file_name_important = true;
-        }
+        DEFAULT_LOG.set_file_name_important(prefix);
     }
 
     /// Log file extension.  
     /// '.log.toml' or '.log'.  
     /// If you don't like the .toml extension, change.  
     pub fn set_file_ext(ext: Extension) {
-        if let Ok(mut logger) = LOGGER.lock() {
-            if !logger.file_ext_important {
-                match ext {
-                    Extension::LogToml => {
-                        logger.file_extension = ".log.toml".to_string();
-                    }
-                    Extension::Log => {
-                        logger.file_extension = ".log".to_string();
-                    }
-                }
-            }
-        }
+        DEFAULT_LOG.set_file_ext(ext);
     }
 
     /// The file extension cannot be changed later.  
@@ -347,20 +450,14 @@ impl Log {
     ///
     /// See also: `Log::set_file_ext()`.  
     pub fn set_file_ext_important(ext: Extension) {
-        Log::set_file_ext(ext);
-        if let Ok(mut logger) = LOGGER.lock() {
-            logger.file_ext_important = true;
-        }
+        DEFAULT_LOG.set_file_ext_important(ext);
     }
 
     /// Example:  
     ///
     /// If 'tic-tac-toe-2020-07-11.log.toml', This is '.log.toml'.  
-    pub fn get_file_ext_str() -> Result<String, String> {
-        match LOGGER.lock() {
-            Ok(logger) => Ok(logger.file_extension.to_string()),
-            Err(e) => Err(e.to_string()),
-        }
+    pub fn get_file_ext_str() -> Result<String, Error> {
+        DEFAULT_LOG.get_file_ext_str()
     }
 
     /// Logs with lower priority than this level will not  
@@ -370,11 +467,7 @@ impl Log {
     /// |<-- High priority --------------- Low priority -->|  
     /// |Fatal< Error < Warn < Notice < Info < Debug <Trace|  
     pub fn set_level(level: Level) {
-        if let Ok(mut logger) = LOGGER.lock() {
-            if !logger.level_important {
-                logger.level = level;
-            }
-        }
+        DEFAULT_LOG.set_level(level);
     }
 
     /// The level cannot be changed later.  
@@ -382,30 +475,57 @@ impl Log {
     ///
     /// See also: `Log::set_level()`.  
     pub fn set_level_important(level: Level) {
-        Log::set_level(level);
-        if let Ok(mut logger) = LOGGER.lock() {
-            logger.level_important = true;
-        }
+        DEFAULT_LOG.set_level_important(level);
     }
 
     /// Example:  
     ///
     /// If 'tic-tac-toe-2020-07-11.log.toml', This is '.log.toml'.  
-    pub fn get_level() -> Result<Level, String> {
-        match LOGGER.lock() {
-            Ok(logger) => Ok(logger.level),
-            Err(e) => Err(e.to_string()),
-        }
+    pub fn get_level() -> Result<Level, Error> {
+        DEFAULT_LOG.get_level()
+    }
+
+    /// Levels per module or target, such as "mygame::ai=Trace,mygame::net=Warn".  
+    /// A directive applies to the module and its sub modules. The longest match wins.  
+    /// Other modules, and logs without a module such as `Log::info()`, use `Log::set_level()`.  
+    /// The module is given by the macros such as `info!()`, `Log::log_t()`, and the `log` and `tracing` features.  
+    /// Default: none.  
+    /// モジュールまたはターゲットごとのレベル。 "mygame::ai=Trace,mygame::net=Warn" など。  
+    /// 指定は、そのモジュールとサブ・モジュールに適用します。一番長く一致したものが勝ちます。  
+    /// 他のモジュールと、 `Log::info()` のようなモジュールの無いログは `Log::set_level()` を使います。  
+    /// モジュールは `info!()` などのマクロ、 `Log::log_t()` 、 `log` と `tracing` フィーチャーが渡します。  
+    /// デフォルト: 無し。  
+    ///
+    /// ```no_run
+    /// use casual_logger::{Level, Log};
+    ///
+    /// Log::set_level(Level::Info);
+    /// Log::set_level_directives("mygame::ai=Trace,mygame::net=Warn".parse().unwrap());
+    /// ```
+    ///
+    /// See also: `Log::set_level_directives_important()`.  
+    pub fn set_level_directives(directives: LevelDirectives) {
+        DEFAULT_LOG.set_level_directives(directives);
+    }
+
+    /// The level directives cannot be changed later.  
+    /// レベル指定は後で変更できません。  
+    ///
+    /// See also: `Log::set_level_directives()`.  
+    pub fn set_level_directives_important(directives: LevelDirectives) {
+        DEFAULT_LOG.set_level_directives_important(directives);
+    }
+
+    /// Levels per module or target.  
+    /// モジュールまたはターゲットごとのレベル。  
+    pub fn get_level_directives() -> Result<LevelDirectives, Error> {
+        DEFAULT_LOG.get_level_directives()
     }
 
     /// You probably don't need to set this. Default: 7.  
     /// Check the StartDate in the file name and delete it if it is old.  
     pub fn set_retention_days(days: i64) {
-        if let Ok(mut logger) = LOGGER.lock() {
-            if !logger.retention_days_important {
-                logger.retention_days = days;
-            }
-        }
+        DEFAULT_LOG.set_retention_days(days);
     }
 
     /// The file retention days cannot be changed later.  
@@ -413,29 +533,73 @@ impl Log {
     ///
     /// See also: `Log::set_retention_days()`.  
     pub fn set_retention_days_important(retention_days: i64) {
-        Log::set_retention_days(retention_days);
-        if let Ok(mut logger) = LOGGER.lock() {
-            logger.retention_days_important = true;
-        }
+        DEFAULT_LOG.set_retention_days_important(retention_days);
     }
 
     /// The file retention days.  
     /// ファイル保持日数。  
-    pub fn get_retention_days() -> Result<i64, String> {
-        match LOGGER.lock() {
-            Ok(logger) => Ok(logger.retention_days),
-            Err(e) => Err(e.to_string()),
-        }
+    pub fn get_retention_days() -> Result<i64, Error> {
+        DEFAULT_LOG.get_retention_days()
+    }
+
+    /// Maximum number of files. Default: 0 (Unlimited).  
+    /// `Log::remove_old_logs()` deletes the oldest files first.  
+    /// It can be used together with the retention days.  
+    /// ファイルの最大数。デフォルト: 0（無制限）。  
+    /// `Log::remove_old_logs()` は古いファイルから削除します。  
+    /// 保持日数と一緒に使えます。  
+    ///
+    /// See also: `Log::set_retention_files_important()`.  
+    pub fn set_retention_files(count: usize) {
+        DEFAULT_LOG.set_retention_files(count);
+    }
+
+    /// The file retention count cannot be changed later.  
+    /// ファイル保持数は後で変更できません。  
+    ///
+    /// See also: `Log::set_retention_files()`.  
+    pub fn set_retention_files_important(count: usize) {
+        DEFAULT_LOG.set_retention_files_important(count);
+    }
+
+    /// Maximum number of files.  
+    /// ファイルの最大数。  
+    pub fn get_retention_files() -> Result<usize, Error> {
+        DEFAULT_LOG.get_retention_files()
+    }
+
+    /// Maximum total size of files in bytes. Default: 0 (Unlimited).  
+    /// `Log::remove_old_logs()` deletes the oldest files first.  
+    /// The file being written, or else the newest file, is always kept.  
+    /// It can be used together with the retention days.  
+    /// ファイルの最大合計サイズ（バイト）。デフォルト: 0（無制限）。  
+    /// `Log::remove_old_logs()` は古いファイルから削除します。  
+    /// 書き込み中のファイル、無ければ一番新しいファイルは常に残します。  
+    /// 保持日数と一緒に使えます。  
+    ///
+    /// See also: `Log::set_retention_size_important()`.  
+    pub fn set_retention_size(bytes: u64) {
+        DEFAULT_LOG.set_retention_size(bytes);
+    }
+
+    /// The file retention size cannot be changed later.  
+    /// ファイル保持サイズは後で変更できません。  
+    ///
+    /// See also: `Log::set_retention_size()`.  
+    pub fn set_retention_size_important(bytes: u64) {
+        DEFAULT_LOG.set_retention_size_important(bytes);
+    }
+
+    /// Maximum total size of files in bytes.  
+    /// ファイルの最大合計サイズ（バイト）。  
+    pub fn get_retention_size() -> Result<u64, Error> {
+        DEFAULT_LOG.get_retention_size()
     }
 
     /// You probably don't need to set this. Default: 30.  
     /// Wait for seconds logging to complete.  
     pub fn set_timeout_secs(secs: u64) {
-        if let Ok(mut logger) = LOGGER.lock() {
-            if !logger.timeout_secs_important {
-                logger.timeout_secs = secs;
-            }
-        }
+        DEFAULT_LOG.set_timeout_secs(secs);
     }
 
     /// The timeout seconds cannot be changed later.  
@@ -443,28 +607,258 @@ impl Log {
     ///
     /// See also: `Log::set_timeout_secs()`.  
     pub fn set_timeout_secs_important(secs: u64) {
-        Log::set_timeout_secs(secs);
-        if let Ok(mut logger) = LOGGER.lock() {
-            logger.timeout_secs_important = true;
-        }
+        DEFAULT_LOG.set_timeout_secs_important(secs);
     }
 
     /// The timeout seconds.  
     /// タイムアウト秒。  
-    pub fn get_timeout_secs() -> Result<u64, String> {
-        match LOGGER.lock() {
-            Ok(logger) => Ok(logger.timeout_secs),
-            Err(e) => Err(e.to_string()),
-        }
+    pub fn get_timeout_secs() -> Result<u64, Error> {
+        DEFAULT_LOG.get_timeout_secs()
+    }
+
+    /// Directory where log files are placed. Default: ".".  
+    /// If the directory does not exist, it will be created.  
+    /// Rotation and `Log::remove_old_logs()` use this directory.  
+    /// ログ・ファイルを置くディレクトリー。デフォルト: "."。  
+    /// ディレクトリーが無ければ作成します。  
+    /// ローテーションと `Log::remove_old_logs()` はこのディレクトリーを使います。  
+    ///
+    /// See also: `Log::set_dir_important()`.  
+    pub fn set_dir(dir: &str) {
+        DEFAULT_LOG.set_dir(dir);
+    }
+
+    /// The directory cannot be changed later.  
+    /// ディレクトリーは後で変更できません。  
+    ///
+    /// See also: `Log::set_dir()`.  
+    pub fn set_dir_important(dir: &str) {
+        DEFAULT_LOG.set_dir_important(dir);
+    }
+
+    /// Directory where log files are placed.  
+    /// ログ・ファイルを置くディレクトリー。  
+    pub fn get_dir() -> Result<String, Error> {
+        DEFAULT_LOG.get_dir()
+    }
+
+    /// Maximum file size in bytes. Default: 0 (Unlimited).  
+    /// If the file exceeds this size, it rotates to a numbered part  
+    /// such as 'tic-tac-toe-2020-07-11.1.log.toml' on the same day.  
+    /// 最大ファイル・サイズ（バイト）。デフォルト: 0（無制限）。  
+    /// ファイルがこの大きさを超えると、同じ日のうちに  
+    /// 'tic-tac-toe-2020-07-11.1.log.toml' のような番号付きの部に切り替わります。  
+    ///
+    /// See also: `Log::set_max_file_size_important()`.  
+    pub fn set_max_file_size(bytes: u64) {
+        DEFAULT_LOG.set_max_file_size(bytes);
+    }
+
+    /// The maximum file size cannot be changed later.  
+    /// 最大ファイル・サイズは後で変更できません。  
+    ///
+    /// See also: `Log::set_max_file_size()`.  
+    pub fn set_max_file_size_important(bytes: u64) {
+        DEFAULT_LOG.set_max_file_size_important(bytes);
+    }
+
+    /// Maximum file size in bytes.  
+    /// 最大ファイル・サイズ（バイト）。  
+    pub fn get_max_file_size() -> Result<u64, Error> {
+        DEFAULT_LOG.get_max_file_size()
+    }
+
+    /// Rotation period. Default: `Rotation::Daily`.  
+    /// The date and time in the file name follows this period.  
+    /// ローテーション周期。デフォルト: `Rotation::Daily`。  
+    /// ファイル名の日時はこの周期に従います。  
+    ///
+    /// The retention is judged by the StartDate in the file name,  
+    /// so keep the retention days longer than the rotation period.  
+    /// 保持はファイル名の開始日で判定するので、  
+    /// 保持日数はローテーション周期より長くしてください。  
+    ///
+    /// See also: `Log::set_rotation_important()`.  
+    pub fn set_rotation(rotation: Rotation) {
+        DEFAULT_LOG.set_rotation(rotation);
+    }
+
+    /// The rotation period cannot be changed later.  
+    /// ローテーション周期は後で変更できません。  
+    ///
+    /// See also: `Log::set_rotation()`.  
+    pub fn set_rotation_important(rotation: Rotation) {
+        DEFAULT_LOG.set_rotation_important(rotation);
+    }
+
+    /// Rotation period.  
+    /// ローテーション周期。  
+    pub fn get_rotation() -> Result<Rotation, Error> {
+        DEFAULT_LOG.get_rotation()
+    }
+
+    /// What to do with the log while the file cannot be written.  
+    /// Default: `Fallback::Memory(1024 * 1024)`.  
+    /// Errors are recorded anyway. See also: `Log::last_error()`.  
+    /// ファイルに書けない間、ログをどうするか。  
+    /// デフォルト: `Fallback::Memory(1024 * 1024)`。  
+    /// どの場合もエラーは記録します。  
+    ///
+    /// See also: `Log::set_fallback_important()`.  
+    pub fn set_fallback(fallback: Fallback) {
+        DEFAULT_LOG.set_fallback(fallback);
+    }
+
+    /// The fallback cannot be changed later.  
+    /// 代替手段は後で変更できません。  
+    ///
+    /// See also: `Log::set_fallback()`.  
+    pub fn set_fallback_important(fallback: Fallback) {
+        DEFAULT_LOG.set_fallback_important(fallback);
+    }
+
+    /// What to do with the log while the file cannot be written.  
+    /// ファイルに書けない間、ログをどうするか。  
+    pub fn get_fallback() -> Result<Fallback, Error> {
+        DEFAULT_LOG.get_fallback()
+    }
+
+    /// Maximum number of logs waiting to be written. Default: 0, unlimited.  
+    /// When the queue is full, `Log::set_overflow()` decides what to do.  
+    /// 書き込みを待つログの最大数。デフォルト: 0 で無制限。  
+    /// キューが満杯のとき、どうするかは `Log::set_overflow()` で決めます。  
+    ///
+    /// See also: `Log::set_queue_capacity_important()`.  
+    pub fn set_queue_capacity(capacity: usize) {
+        DEFAULT_LOG.set_queue_capacity(capacity);
+    }
+
+    /// The queue capacity cannot be changed later.  
+    /// キューの容量は後で変更できません。  
+    ///
+    /// See also: `Log::set_queue_capacity()`.  
+    pub fn set_queue_capacity_important(capacity: usize) {
+        DEFAULT_LOG.set_queue_capacity_important(capacity);
+    }
+
+    /// Maximum number of logs waiting to be written.  
+    /// 書き込みを待つログの最大数。  
+    pub fn get_queue_capacity() -> Result<usize, Error> {
+        DEFAULT_LOG.get_queue_capacity()
+    }
+
+    /// What to do when the queue is full. Default: `Overflow::Block`.  
+    /// When logs are dropped, a Warn log with the number of them is  
+    /// written once there is space again.  
+    /// キューが満杯のときにどうするか。デフォルト: `Overflow::Block`。  
+    /// ログを捨てたときは、空きができたら、その数を Warn ログで書きます。  
+    ///
+    /// See also: `Log::set_overflow_important()`, `Log::dropped_count()`.  
+    pub fn set_overflow(overflow: Overflow) {
+        DEFAULT_LOG.set_overflow(overflow);
+    }
+
+    /// The overflow policy cannot be changed later.  
+    /// あふれたときの方針は後で変更できません。  
+    ///
+    /// See also: `Log::set_overflow()`.  
+    pub fn set_overflow_important(overflow: Overflow) {
+        DEFAULT_LOG.set_overflow_important(overflow);
+    }
+
+    /// What to do when the queue is full.  
+    /// キューが満杯のときにどうするか。  
+    pub fn get_overflow() -> Result<Overflow, Error> {
+        DEFAULT_LOG.get_overflow()
+    }
+
+    /// After writing a log at or above this level, the file is synchronized  
+    /// to the disk, so that it survives a power loss. Default: None.  
+    /// For example, `Some(Level::Error)` synchronizes on Error and Fatal.  
+    /// このレベル以上のログを書いた後、電源が落ちても残るよう、  
+    /// ファイルをディスクへ同期します。デフォルト: None。  
+    /// 例えば `Some(Level::Error)` は Error と Fatal で同期します。  
+    ///
+    /// See also: `Log::set_sync_level_important()`, `Log::sync()`.  
+    pub fn set_sync_level(level: Option<Level>) {
+        DEFAULT_LOG.set_sync_level(level);
+    }
+
+    /// The sync level cannot be changed later.  
+    /// 同期するレベルは後で変更できません。  
+    ///
+    /// See also: `Log::set_sync_level()`.  
+    pub fn set_sync_level_important(level: Option<Level>) {
+        DEFAULT_LOG.set_sync_level_important(level);
+    }
+
+    /// The file is synchronized after writing a log at or above this level.  
+    /// このレベル以上のログを書いた後、ファイルを同期します。  
+    pub fn get_sync_level() -> Result<Option<Level>, Error> {
+        DEFAULT_LOG.get_sync_level()
+    }
+
+    /// How to write the log. Default: `WriteMode::Background`.  
+    /// ログをどう書き込むか。デフォルト: `WriteMode::Background`。  
+    ///
+    /// See also: `Log::set_write_mode_important()`.  
+    pub fn set_write_mode(write_mode: WriteMode) {
+        DEFAULT_LOG.set_write_mode(write_mode);
+    }
+
+    /// The write mode cannot be changed later.  
+    /// 書き込み方式は後で変更できません。  
+    ///
+    /// See also: `Log::set_write_mode()`.  
+    pub fn set_write_mode_important(write_mode: WriteMode) {
+        DEFAULT_LOG.set_write_mode_important(write_mode);
+    }
+
+    /// How to write the log.  
+    /// ログをどう書き込むか。  
+    pub fn get_write_mode() -> Result<WriteMode, Error> {
+        DEFAULT_LOG.get_write_mode()
+    }
+
+    /// Number of logs dropped because the queue was full.  
+    /// キューが満杯で捨てたログの数。  
+    pub fn dropped_count() -> u64 {
+        DEFAULT_LOG.dropped_count()
+    }
+
+    /// Compress rotated files. Default: false.  
+    /// When the file is rotated, the closed file is compressed to  
+    /// 'tic-tac-toe-2020-07-11.log.toml.gz' in the background.  
+    /// `Log::remove_old_logs()` also removes the compressed files.  
+    /// If compression fails, the file is kept as it is, and the error goes to `Log::last_error()`.  
+    /// ローテーションされたファイルを圧縮します。デフォルト: false。  
+    /// ファイルがローテーションされると、閉じたファイルをバックグラウンドで  
+    /// 'tic-tac-toe-2020-07-11.log.toml.gz' に圧縮します。  
+    /// `Log::remove_old_logs()` は圧縮ファイルも削除します。  
+    /// 圧縮に失敗したら、ファイルはそのまま残り、エラーは `Log::last_error()` へ行きます。  
+    ///
+    /// See also: `Log::set_compress_important()`.  
+    pub fn set_compress(compress: bool) {
+        DEFAULT_LOG.set_compress(compress);
+    }
+
+    /// The compression cannot be changed later.  
+    /// 圧縮は後で変更できません。  
+    ///
+    /// See also: `Log::set_compress()`.  
+    pub fn set_compress_important(compress: bool) {
+        DEFAULT_LOG.set_compress_important(compress);
+    }
+
+    /// Compress rotated files.  
+    /// ローテーションされたファイルを圧縮します。  
+    pub fn get_compress() -> Result<bool, Error> {
+        DEFAULT_LOG.get_compress()
     }
 
     /// Optimization.
     pub fn set_opt(optimization: Opt) {
-        if let Ok(mut opt_state) = OPT_STATE.lock() {
-            if !opt_state.opt_important {
-                opt_state.set(optimization);
-            }
-        }
+        DEFAULT_LOG.set_opt(optimization);
     }
 
     /// The optimization cannot be changed later.  
@@ -472,46 +866,249 @@ impl Log {
     ///
     /// See also: `Log::set_opt()`.  
     pub fn set_opt_important(optimization: Opt) {
-        Log::set_opt(optimization);
-        if let Ok(mut opt_state) = OPT_STATE.lock() {
-            opt_state.opt_important = true;
-        }
+        DEFAULT_LOG.set_opt_important(optimization);
     }
 
     /// Optimization.  
     /// 最適化。  
-    pub fn get_opt() -> Result<Opt, String> {
-        match OPT_STATE.lock() {
-            Ok(opt_state) => Ok(opt_state.opt),
-            Err(e) => Err(e.to_string()),
-        }
+    pub fn get_opt() -> Result<Opt, Error> {
+        DEFAULT_LOG.get_opt()
+    }
+
+    /// Check the settings, then apply them all at once.  
+    /// No log is written with half of them applied.  
+    /// If invalid, nothing is changed.  
+    /// Settings already set with `_important` are not changed, as with `Log::set_xxx()`.  
+    /// 設定を検査してから、全部を一度に適用します。  
+    /// 半分だけ適用された状態でログが書かれることはありません。  
+    /// 不正なら、何も変更しません。  
+    /// `Log::set_xxx()` と同じく、 `_important` で設定済みのものは変更しません。  
+    ///
+    /// See also: `LogConfig`.  
+    pub fn apply_config(config: &LogConfig) -> Result<(), Error> {
+        DEFAULT_LOG.apply_config(config)
+    }
+
+    /// Read the settings from a TOML file, then apply them at once.  
+    /// The keys are the names of `Log::set_xxx()` without `set_`.  
+    /// A key ending with `_important` is set as `Log::set_xxx_important()`,  
+    /// so the application's file wins over the library's defaults.  
+    /// An unknown key is an error, and then nothing is changed.  
+    /// TOML ファイルから設定を読み、一度に適用します。  
+    /// キーは `Log::set_xxx()` から `set_` を除いた名前です。  
+    /// `_important` で終わるキーは `Log::set_xxx_important()` として設定するので、  
+    /// アプリケーションのファイルがライブラリーのデフォルトに勝ちます。  
+    /// 未知のキーはエラーで、そのときは何も変更しません。  
+    ///
+    /// Example of casual_logger.toml:  
+    /// casual_logger.toml の例:  
+    ///
+    /// ```toml
+    /// file_name = "tic-tac-toe"
+    /// file_ext = "LogToml"
+    /// dir = "logs"
+    /// level_important = "Info"
+    /// level_directives = "mygame::ai=Trace,mygame::net=Warn"
+    /// retention_days = 2
+    /// timeout_secs = 30
+    /// opt = "Release"
+    /// rotation = "Daily"          # "Hourly", "Weekly" or "Interval(1800)" seconds.
+    /// fallback = "Memory(1048576)" # "Discard" or "Stderr".
+    /// overflow = "DropBelow(Warn)" # "Block", "DropNewest" or "DropOldest".
+    /// sync_level = "Error"        # Or "None".
+    /// write_mode = "Background"   # Or "Synchronous".
+    /// ```
+    ///
+    /// See also: `LogConfig`.  
+    pub fn load_config(path: &str) -> Result<(), Error> {
+        DEFAULT_LOG.load_config(path)
+    }
+
+    /// Load the settings file as `Log::load_config()`,  
+    /// then re-read it every `interval_secs` on a new thread.  
+    /// When the file changes, the level and the other settings are applied at runtime.  
+    /// If the settings in effect changed, a Notice log "casual_logger reloaded the configuration."  
+    /// is written at any level.  
+    /// Settings set with `_important`, by the code, the environment variables or the file itself,  
+    /// are not changed.  
+    /// If the changed file is invalid, the error goes to `Log::last_error()` and nothing is changed.  
+    /// Calling it again watches the new file instead.  
+    /// `Log::load_config()` のように設定ファイルを読み、  
+    /// 新しいスレッドで `interval_secs` ごとに読み直します。  
+    /// ファイルが変わると、レベルと他の設定を実行中に適用します。  
+    /// 有効な設定が変わったら、どのレベルでも Notice ログ "casual_logger reloaded the configuration." を書きます。  
+    /// コード、環境変数、ファイル自身によって `_important` で設定されたものは変更しません。  
+    /// 変更されたファイルが不正なら、エラーは `Log::last_error()` へ行き、何も変更しません。  
+    /// もう一度呼ぶと、代わりに新しいファイルを見張ります。  
+    ///
+    /// ```no_run
+    /// use casual_logger::Log;
+    ///
+    /// Log::watch_config("casual_logger.toml", 5).unwrap();
+    /// ```
+    ///
+    /// See also: `Log::unwatch_config()`.  
+    pub fn watch_config(path: &str, interval_secs: u64) -> Result<(), Error> {
+        DEFAULT_LOG.watch_config(path, interval_secs)
+    }
+
+    /// Stop watching the settings file. The settings are kept.  
+    /// 設定ファイルの見張りを止めます。設定はそのままです。  
+    pub fn unwatch_config() {
+        DEFAULT_LOG.unwatch_config();
+    }
+
+    /// Write the logs of the `log` crate, such as `log::info!()`, with `Log::`.  
+    /// Requires the `log` feature.  
+    /// `log::Level` is mapped to the `Level` of the same name.  
+    /// The `log` crate has no Notice and Fatal, so `Log::set_level(Level::Notice)` hides `log::info!()`.  
+    /// The target, module path, file and line are written as the keys  
+    /// "Target", "ModulePath", "File" and "Line".  
+    /// `log::info!()` などの `log` クレートのログを `Log::` で書きます。  
+    /// `log` フィーチャーが必要です。  
+    /// `log::Level` は同じ名前の `Level` に対応します。  
+    /// `log` クレートには Notice と Fatal が無いので、 `Log::set_level(Level::Notice)` は `log::info!()` を隠します。  
+    /// ターゲット、モジュール・パス、ファイル、行は、  
+    /// キー "Target" 、 "ModulePath" 、 "File" 、 "Line" として書きます。  
+    ///
+    /// # Returns
+    ///
+    /// Error if a logger of the `log` crate is already set.  
+    /// `log` クレートのロガーが既に設定されていればエラー。  
+    #[cfg(feature = "log")]
+    pub fn init_log_facade() -> Result<(), Error> {
+        log_facade::init()
     }
 
     /// # Returns
     ///
-    /// Number of deleted log files.
+    /// Number of deleted log files.  
+    /// If the log files could not be searched, 0 and the reason goes to `Log::last_error()`.  
+    /// 削除したログ・ファイル数。  
+    /// ログ・ファイルを探せなかったら 0 で、理由は `Log::last_error()` へ行きます。  
     pub fn remove_old_logs() -> usize {
-        let remove_num = if let Ok(logger) = LOGGER.lock() {
-            // Do not call 'Log::xxxxx()' in this code block.
-
-            let remove_num = logger.remove_old_logs();
-
-            match Logger::get_optimization() {
-                Opt::BeginnersSupport | Opt::Development => {
-                    println!(
-                        "casual_logger   | Remove {} log file(s).
-                | If you don't want this message, set `Log::set_opt(Opt::Release);`.",
-                        remove_num
-                    );
-                }
-                Opt::Release => {}
-            }
-            remove_num
+        DEFAULT_LOG.remove_old_logs()
+    }
+
+    /// Remove old log files automatically. Default: false.  
+    /// Each time a file is opened, at the first write or by rotation,  
+    /// the same as `Log::remove_old_logs()` is done in the background.  
+    /// Nothing is displayed.  
+    /// 古いログ・ファイルを自動で削除します。デフォルト: false。  
+    /// 最初の書き込みやローテーションでファイルを開くたびに、  
+    /// `Log::remove_old_logs()` と同じことをバックグラウンドで行います。  
+    /// 何も表示しません。  
+    ///
+    /// See also: `Log::set_auto_remove_important()`.  
+    pub fn set_auto_remove(auto_remove: bool) {
+        DEFAULT_LOG.set_auto_remove(auto_remove);
+    }
+
+    /// The automatic removal cannot be changed later.  
+    /// 自動削除は後で変更できません。  
+    ///
+    /// See also: `Log::set_auto_remove()`.  
+    pub fn set_auto_remove_important(auto_remove: bool) {
+        DEFAULT_LOG.set_auto_remove_important(auto_remove);
+    }
+
+    /// Remove old log files automatically.  
+    /// 古いログ・ファイルを自動で削除します。  
+    pub fn get_auto_remove() -> Result<bool, Error> {
+        DEFAULT_LOG.get_auto_remove()
+    }
+
+    /// Delete old log files, and report them.  
+    /// Nothing is displayed. Use it to audit the retention.  
+    /// 古いログ・ファイルを削除して、報告します。  
+    /// 何も表示しません。保持の監査に使ってください。  
+    ///
+    /// # Returns
+    ///
+    /// Deleted log files, and log files that could not be deleted.  
+    /// Error, if the log files could not be searched.  
+    /// 削除したログ・ファイルと、削除できなかったログ・ファイル。  
+    /// ログ・ファイルを探せなかったらエラー。  
+    pub fn remove_old_logs_with_report() -> Result<RemoveReport, Error> {
+        DEFAULT_LOG.remove_old_logs_with_report()
+    }
+
+    /// List the log files that `Log::remove_old_logs()` would delete, and why.  
+    /// Nothing is deleted. (Dry run)  
+    /// `Log::remove_old_logs()` が削除するログ・ファイルと、その理由を一覧します。  
+    /// 何も削除しません。（ドライ・ラン）  
+    pub fn plan_old_logs() -> Result<Vec<OldLog>, Error> {
+        DEFAULT_LOG.plan_old_logs()
+    }
+
+    /// The last error that occurred in the logger.  
+    /// Errors are not displayed, so check here.  
+    /// ロガーで起きた最後のエラー。  
+    /// エラーは表示しないので、ここで確認してください。  
+    pub fn last_error() -> Option<Error> {
+        if let Ok(error_state) = ERROR_STATE.lock() {
+            error_state.last.clone()
+        } else {
+            None
+        }
+    }
+
+    /// Number of errors that occurred in the logger.  
+    /// ロガーで起きたエラーの数。  
+    pub fn error_count() -> u64 {
+        if let Ok(error_state) = ERROR_STATE.lock() {
+            error_state.count
         } else {
-            // Setup failed. Continue with the default settings.
             0
-        };
-        remove_num
+        }
+    }
+
+    /// Called each time an error occurs in the logger.  
+    /// It may be called from the background thread.  
+    /// Do not call 'Log::xxxxx()' in the callback.  
+    /// ロガーでエラーが起きるたびに呼ばれます。  
+    /// バックグラウンドのスレッドから呼ばれることがあります。  
+    /// コールバックの中で 'Log::xxxxx()' を呼ばないでください。  
+    ///
+    /// Example:  
+    ///
+    /// ```
+    /// use casual_logger::Log;
+    ///
+    /// Log::set_error_callback(|error| {
+    ///     eprintln!("casual_logger   | {}", error);
+    /// });
+    /// ```
+    pub fn set_error_callback<F>(callback: F)
+    where
+        F: Fn(&Error) + Send + Sync + 'static,
+    {
+        if let Ok(mut error_state) = ERROR_STATE.lock() {
+            error_state.callback = Some(Arc::new(callback));
+        }
+    }
+
+    /// Record the error, and call the callback.  
+    /// エラーを記録して、コールバックを呼びます。  
+    pub(crate) fn report_error(error: Error) {
+        // Out of ERROR_STATE.lock().
+        if let Some(callback) = Log::record_error(&error) {
+            callback(&error);
+        }
+    }
+
+    /// Record the error, and return the callback without calling it.  
+    /// While `Log::` is being initialized, the callback cannot be called.  
+    /// エラーを記録して、コールバックを呼ばずに返します。  
+    /// `Log::` の初期化中は、コールバックを呼べません。  
+    pub(crate) fn record_error(error: &Error) -> Option<ErrorCallback> {
+        if let Ok(mut error_state) = ERROR_STATE.lock() {
+            error_state.count += 1;
+            error_state.last = Some(error.clone());
+            error_state.callback.clone()
+        } else {
+            None
+        }
     }
 
     /// Wait for logging to complete.  
@@ -526,568 +1123,221 @@ impl Log {
     }
 
     /// Wait for logging to complete.  
+    /// Returns when everything logged before the call is written to the file.  
+    /// ログの書き込みが完了するのを待ちます。  
+    /// 呼び出し前に記録したものが全てファイルに書き込まれたら戻ります。  
     ///
     /// See also: Log::set_timeout_secs(), Log::set_opt().  
     pub fn flush() {
-        let (timeout_secs, opt) = if let Ok(logger) = LOGGER.lock() {
-            (logger.timeout_secs, Logger::get_optimization())
-        } else {
-            // Error
-            (0, Opt::BeginnersSupport)
-        };
-
-        Log::wait_for_logging_to_complete(timeout_secs, |secs, message| {
-            // Do not call 'Log::xxxxx()' in this code block.
-            match opt {
-                Opt::Development => {
-                    // Running background.
-                    println!("casual_logger   | {} sec(s). {}", secs, message,);
-                }
-                Opt::Release | Opt::BeginnersSupport => {}
-            }
-        });
+        DEFAULT_LOG.flush();
+    }
+
+    /// Same as Log::flush(), and also synchronizes the file to the disk.  
+    /// Log::flush() と同じで、さらにファイルをディスクへ同期します。  
+    ///
+    /// See also: Log::set_timeout_secs(), Log::set_opt(), Log::set_sync_level().  
+    pub fn sync() {
+        DEFAULT_LOG.sync();
+    }
+
+    /// Returns a guard that performs Log::flush() when dropped.  
+    /// Keep it until the end of main, then you will not forget to flush.  
+    /// ドロップされたときに Log::flush() を行うガードを返します。  
+    /// main の終わりまで持っておけば、フラッシュを忘れません。  
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use casual_logger::Log;
+    ///
+    /// let _guard = Log::init_guard();
+    /// Log::info("Hello, world!!");
+    /// // Flushed here.
+    /// ```
+    ///
+    /// Note: `let _ = Log::init_guard();` drops the guard at once.  
+    /// 注意: `let _ = Log::init_guard();` はガードをすぐに破棄します。  
+    pub fn init_guard() -> FlushGuard {
+        FlushGuard::new()
+    }
+
+    /// Performs Log::flush() when the process exits normally,  
+    /// such as returning from main or std::process::exit().  
+    /// It is not performed on abort or on a signal. Calling again does nothing.  
+    /// main から戻る、std::process::exit() など、  
+    /// プロセスが正常に終了するときに Log::flush() を行います。  
+    /// アボートやシグナルのときは行いません。 2回目以降の呼び出しは何もしません。  
+    ///
+    /// If it cannot be registered, the error goes to `Log::last_error()`.  
+    /// 登録できなければ、エラーは `Log::last_error()` へ行きます。  
+    pub fn flush_at_exit() {
+        FlushGuard::register_at_exit();
     }
 
     fn print_message(queue_len: Option<usize>) -> String {
-        format!(
-            "{}",
-            if let Some(queue_len_val) = queue_len {
-                if 0 < queue_len_val {
-                    format!("{} table(s) left. ", queue_len_val)
-                } else {
-                    "".to_string()
-                }
-            } else {
-                "".to_string()
-            },
-            /*
-            if let Ok(mem) = mem_info() {
-                format!(
-                    "Mem=|Total {}|Avail {}|Buffers {}|Cached {}|Free {}|SwapFree {}|SwapTotal {}| ",
-                    mem.total, mem.avail, mem.buffers, mem.cached, mem.free, mem.swap_free, mem.swap_total
-                )
-            } else {
-                "".to_string()
-            }
-            */
-        )
-        .trim_end()
-        .to_string()
-    }
-    /// Wait for logging to complete.
-    fn wait_for_logging_to_complete<F>(timeout_secs: u64, count_down: F)
-    where
-        F: Fn(u64, String),
-    {
-        let mut elapsed_milli_secs = 0;
-
-        // Wait a moment for the thread just created to write.
-        // 今作成されたスレッドが書き込むのを少し待ちます。
-        thread::sleep(std::time::Duration::from_millis(20));
-        elapsed_milli_secs += 20;
-
-        let mut empty_que_count = 0;
-        // let mut participating_threads_count = 0;
-        // || 0 < participating_threads_count
-        while empty_que_count < 2 && elapsed_milli_secs < timeout_secs * 1000 {
-            let mut queue_len = None;
-            if let Ok(reserve_target) = RESERVE_TARGET.lock() {
-                if reserve_target.is_t() {
-                    if let Ok(queue) = QUEUE_T.lock() {
-                        if queue.is_empty() {
-                            // Completed.
-                            break;
-                        }
-                        queue_len = Some(queue.len());
-                    }
-                } else {
-                    if let Ok(queue) = QUEUE_F.lock() {
-                        if queue.is_empty() {
-                            // Completed.
-                            break;
-                        }
-                        queue_len = Some(queue.len());
-                    }
-                }
-            }
-
-            // Out of QUEUE.lock().
-            if let Some(completed) = Log::flush_target_queue() {
-                if completed {
-                    // Reset.
-                    empty_que_count = 0;
-                } else {
-                    empty_que_count += 1;
-                }
-            } else {
-                // TODO Error.
-                // Reset.
-                empty_que_count = 0;
-            }
-            if elapsed_milli_secs % 1000 == 0 {
-                count_down(elapsed_milli_secs / 1000, Log::print_message(queue_len));
-            }
-
-            /*
-            participating_threads_count =
-                if let Ok(participating_threads_counter) = PARTICIPANTING_THREADS_COUNTER.lock() {
-                    participating_threads_counter.get_thread_count()
-                } else {
-                    // Error
-                    0
-                };
-            */
-            thread::sleep(std::time::Duration::from_millis(20));
-            elapsed_milli_secs += 20;
+        match queue_len {
+            Some(queue_len_val) if 0 < queue_len_val => format!("{} table(s) left.", queue_len_val),
+            _ => "".to_string(),
         }
     }
 
     /// Check level.
     pub fn enabled(level: Level) -> bool {
-        if let Ok(logger) = LOGGER.lock() {
-            if logger.enabled(level) {
-                return true;
-            }
-        }
-        false
+        DEFAULT_LOG.enabled(level)
+    }
+
+    /// Check level for the module or target, such as `module_path!()`.  
+    /// See also: `Log::set_level_directives()`.  
+    /// `module_path!()` のような、モジュールまたはターゲットについてレベルを検査します。  
+    pub fn enabled_for(level: Level, target: &str) -> bool {
+        DEFAULT_LOG.enabled_for(level, target)
+    }
+
+    /// Write at the level, if it is enabled for the module or target.  
+    /// Used by the macros such as `info!()`. For Fatal, use `Log::fatal_t()`.  
+    /// モジュールまたはターゲットについて有効なら、そのレベルで書きます。  
+    /// `info!()` などのマクロが使います。 Fatal には `Log::fatal_t()` を使ってください。  
+    pub fn log_t(level: Level, target: &str, message: &str, table: &mut Table) {
+        DEFAULT_LOG.log_t(level, target, message, table);
     }
 
     /// Trace level. No trailing newline.
     pub fn trace(message: &str) {
-        if Log::enabled(Level::Trace) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Trace,
-                message,
-                false,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.trace(message);
     }
 
     /// Trace level. There is a trailing newline.
     pub fn traceln(message: &str) {
-        if Log::enabled(Level::Trace) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Trace,
-                message,
-                true,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.traceln(message);
     }
 
     /// Trace level. No trailing newline. Use table.
     pub fn trace_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Trace) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Trace;
-            table.message = message.to_string();
-            table.message_trailing_newline = false;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.trace_t(message, table);
     }
 
     /// Trace level. There is a trailing newline. Use table.
     pub fn traceln_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Trace) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Trace;
-            table.message = message.to_string();
-            table.message_trailing_newline = true;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.traceln_t(message, table);
     }
 
     /// Debug level. No trailing newline.
     pub fn debug(message: &str) {
-        if Log::enabled(Level::Debug) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Debug,
-                message,
-                false,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.debug(message);
     }
 
     /// Debug level. There is a trailing newline.
     pub fn debugln(message: &str) {
-        if Log::enabled(Level::Debug) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Debug,
-                message,
-                true,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.debugln(message);
     }
 
     /// Debug level. No trailing newline. Use table.
     pub fn debug_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Debug) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Debug;
-            table.message = message.to_string();
-            table.message_trailing_newline = false;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.debug_t(message, table);
     }
 
     /// Debug level. There is a trailing newline. Use table.
     pub fn debugln_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Debug) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Debug;
-            table.message = message.to_string();
-            table.message_trailing_newline = true;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.debugln_t(message, table);
     }
 
     /// Info level. No trailing newline.
     pub fn info(message: &str) {
-        if Log::enabled(Level::Info) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Info,
-                message,
-                false,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.info(message);
     }
 
     /// Info level. There is a trailing newline.
     pub fn infoln(message: &str) {
-        if Log::enabled(Level::Info) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Info,
-                message,
-                true,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.infoln(message);
     }
 
     /// Info level. No trailing newline. Use table.
     pub fn info_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Info) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Info;
-            table.message = message.to_string();
-            table.message_trailing_newline = false;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.info_t(message, table);
     }
 
     /// Info level. There is a trailing newline. Use table.
     pub fn infoln_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Info) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Info;
-            table.message = message.to_string();
-            table.message_trailing_newline = true;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.infoln_t(message, table);
     }
     /// Notice level. No trailing newline.
     pub fn notice(message: &str) {
-        if Log::enabled(Level::Notice) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Notice,
-                message,
-                false,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.notice(message);
     }
 
     /// Notice level. There is a trailing newline.
     pub fn noticeln(message: &str) {
-        if Log::enabled(Level::Notice) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Notice,
-                message,
-                true,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.noticeln(message);
     }
     /// Notice level. No trailing newline. Use table.
     pub fn notice_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Notice) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Notice;
-            table.message = message.to_string();
-            table.message_trailing_newline = false;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.notice_t(message, table);
     }
 
     /// Notice level. There is a trailing newline. Use table.
     pub fn noticeln_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Notice) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Notice;
-            table.message = message.to_string();
-            table.message_trailing_newline = true;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.noticeln_t(message, table);
     }
 
     /// Warning level. No trailing newline.
     pub fn warn(message: &str) {
-        if Log::enabled(Level::Warn) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Warn,
-                message,
-                false,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.warn(message);
     }
 
     /// Warning level. There is a trailing newline.
     pub fn warnln(message: &str) {
-        if Log::enabled(Level::Warn) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Warn,
-                message,
-                true,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.warnln(message);
     }
 
     /// Warning level. No trailing newline. Use table.
     pub fn warn_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Warn) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Warn;
-            table.message = message.to_string();
-            table.message_trailing_newline = false;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.warn_t(message, table);
     }
 
     /// Warning level. There is a trailing newline. Use table.
     pub fn warnln_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Warn) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Warn;
-            table.message = message.to_string();
-            table.message_trailing_newline = true;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.warnln_t(message, table);
     }
 
     /// Error level. No trailing newline.
     pub fn error(message: &str) {
-        if Log::enabled(Level::Error) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Error,
-                message,
-                false,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.error(message);
     }
 
     /// Error level. There is a trailing newline.
     pub fn errorln(message: &str) {
-        if Log::enabled(Level::Error) {
-            Log::reserve(&InternalTable::from_table(&Table::new(
-                Level::Error,
-                message,
-                true,
-                &Stringifier::create_identify_table_name(Logger::create_seq()),
-            )));
-        }
+        DEFAULT_LOG.errorln(message);
     }
 
     /// Error level. No trailing newline. Use table.
     pub fn error_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Error) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Error;
-            table.message = message.to_string();
-            table.message_trailing_newline = false;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.error_t(message, table);
     }
 
     /// Error level. There is a trailing newline. Use table.
     pub fn errorln_t(message: &str, table: &mut Table) {
-        if Log::enabled(Level::Error) {
-            table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-            table.level = Level::Error;
-            table.message = message.to_string();
-            table.message_trailing_newline = true;
-            Log::reserve(&InternalTable::from_table(table));
-        }
+        DEFAULT_LOG.errorln_t(message, table);
     }
     /// Fatal level. No trailing newline.
     /// Fatal is Panic! Can be used as the first argument of.
     pub fn fatal(message: &str) -> String {
-        // Fatal runs at any level.
-        Log::reserve(&InternalTable::from_table(&Table::new(
-            Level::Fatal,
-            message,
-            false,
-            &Stringifier::create_identify_table_name(Logger::create_seq()),
-        )));
-        // Wait for logging to complete or to timeout.
-        Log::flush();
-        message.to_string()
+        DEFAULT_LOG.fatal(message)
     }
     /// Fatal level. There is a trailing newline.
     /// Fatal is Panic! Can be used as the first argument of.
     pub fn fatalln(message: &str) -> String {
-        // Fatal runs at any level.
-        Log::reserve(&InternalTable::from_table(&Table::new(
-            Level::Fatal,
-            message,
-            true,
-            &Stringifier::create_identify_table_name(Logger::create_seq()),
-        )));
-        // Wait for logging to complete or to timeout.
-        Log::flush();
-        // Append trailing newline.
-        format!("{}{}", message, NEW_LINE).to_string()
+        DEFAULT_LOG.fatalln(message)
     }
 
     /// Fatal level. No trailing newline.
     /// Fatal is Panic! Can be used as the first argument of.
     pub fn fatal_t(message: &str, table: &mut Table) -> String {
-        // Fatal runs at any level.
-        table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-        table.level = Level::Fatal;
-        table.message = message.to_string();
-        table.message_trailing_newline = false;
-        Log::reserve(&InternalTable::from_table(table));
-        // Wait for logging to complete or to timeout.
-        Log::flush();
-        message.to_string()
+        DEFAULT_LOG.fatal_t(message, table)
     }
     /// Fatal level. There is a trailing newline.
     /// Fatal is Panic! Can be used as the first argument of.
     pub fn fatalln_t(message: &str, table: &mut Table) -> String {
-        // Fatal runs at any level.
-        table.base_name = Stringifier::create_identify_table_name(Logger::create_seq());
-        table.level = Level::Fatal;
-        table.message = message.to_string();
-        table.message_trailing_newline = true;
-        Log::reserve(&InternalTable::from_table(table));
-        // Wait for logging to complete or to timeout.
-        Log::flush();
-        // Append trailing newline.
-        format!("{}{}", message, NEW_LINE).to_string()
-    }
-
-    fn reserve(i_table: &InternalTable) {
-        /*
-        if let Ok(mut participating_threads_counter) = PARTICIPANTING_THREADS_COUNTER.lock() {
-            participating_threads_counter.increase_thread_count();
-        }
-        */
-
-        if let Ok(reseve_target) = RESERVE_TARGET.lock() {
-            if reseve_target.is_t() {
-                if let Ok(mut queue) = QUEUE_T.lock() {
-                    queue.push_front(i_table.clone());
-                }
-            } else {
-                if let Ok(mut queue) = QUEUE_F.lock() {
-                    queue.push_front(i_table.clone());
-                }
-            }
-        } else {
-            // TODO Error
-            return;
-        }
-
-        /*
-        if let Ok(mut participating_threads_counter) = PARTICIPANTING_THREADS_COUNTER.lock() {
-            participating_threads_counter.decrease_thread_count();
-        }
-        */
-
-        if let Ok(mut signal) = SIGNAL_CAN_FLUSH.lock() {
-            if signal.can_flush() {
-                signal.set_can_flush(false);
-                thread::spawn(move || {
-                    Log::flush_target_queue();
-                });
-            }
-        }
-    }
-
-    /// Write a some strings from the queue.
-    ///
-    /// # Returns
-    ///
-    /// Some(true) - Complete.
-    /// Some(false) - Not work.
-    /// None - Error.
-    fn flush_target_queue() -> Option<bool> {
-        // By buffering, the number of file writes is reduced.
-        let mut str_buf = String::new();
-
-        // Switch.
-        let flush_target = if let Ok(mut reserve_target) = RESERVE_TARGET.lock() {
-            let old = reserve_target.is_t();
-            reserve_target.switch();
-            old
-        } else {
-            // TODO Error.
-            return None;
-        };
-
-        let mut count = 0;
-        if flush_target {
-            if let Ok(mut queue) = QUEUE_T.lock() {
-                loop {
-                    if let Some(internal_table) = queue.pop_back() {
-                        str_buf.push_str(&internal_table.stringify());
-                        count += 1;
-                    } else {
-                        break;
-                    }
-                }
-            } else {
-                // TODO Error.
-            }
-        } else {
-            if let Ok(mut queue) = QUEUE_F.lock() {
-                loop {
-                    if let Some(internal_table) = queue.pop_back() {
-                        str_buf.push_str(&internal_table.stringify());
-                        count += 1;
-                    } else {
-                        break;
-                    }
-                }
-            } else {
-                // TODO Error.
-            }
-        }
-        // Flush! (Outside the lock on the Queue.)
-        // Write to a log file.
-        // This is time consuming and should be done in a separate thread.
-        if let Ok(mut logger) = LOGGER.lock() {
-            let mut file_buf = BufWriter::new(logger.current_file());
-            // write_all method required to use 'use std::io::Write;'.
-            if let Err(_why) = file_buf.write_all(str_buf.as_bytes()) {
-                // Nothing is output even if log writing fails.
-                // Submitting a message to the competition can result in fouls.
-                // panic!("couldn't write log. : {}",Error::description(&why)),
-                return None;
-            }
-            if let Ok(mut signal) = SIGNAL_CAN_FLUSH.lock() {
-                signal.set_can_flush(true);
-            }
-        }
-
-        Some(0 < count)
+        DEFAULT_LOG.fatalln_t(message, table)
     }
 }
 
@@ -1100,68 +1350,331 @@ pub enum Extension {
     LogToml,
 }
 
-/// The queue number is a Boolean, not a number.
-#[derive(Clone, Copy)]
-struct ReserveTarget {
-    target: bool,
+/// Rotation period of the log file.  
+/// ログ・ファイルのローテーション周期です。  
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rotation {
+    /// Every hour. Example: 'tic-tac-toe-2020-07-11T15.log.toml'.  
+    /// 毎時。  
+    Hourly,
+    /// Every day. Default. Example: 'tic-tac-toe-2020-07-11.log.toml'.  
+    /// 毎日。デフォルト。  
+    #[default]
+    Daily,
+    /// Every week from Monday. Example: 'tic-tac-toe-2020-07-06.log.toml'.  
+    /// 月曜日から毎週。  
+    Weekly,
+    /// Any period. Example: 'tic-tac-toe-2020-07-11T15-30-00.log.toml'.  
+    /// Less than 1 second is the same as `Rotation::Daily`, also in the file name.  
+    /// 任意の周期。  
+    /// 1秒未満なら、ファイル名も含めて `Rotation::Daily` と同じです。  
+    Interval(chrono::Duration),
 }
-impl Default for ReserveTarget {
-    fn default() -> Self {
-        ReserveTarget { target: false }
-    }
+
+/// Error of the logger.  
+/// Logging continues even if an error occurs.  
+/// See also: `Log::last_error()`, `Log::set_error_callback()`.  
+/// ロガーのエラー。  
+/// エラーが起きてもロギングは続きます。  
+#[derive(Clone, Debug)]
+pub enum Error {
+    /// The log file could not be opened.  
+    /// ログ・ファイルを開けませんでした。  
+    Open {
+        path: PathBuf,
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The log could not be written.  
+    /// ログを書けませんでした。  
+    Write {
+        path: PathBuf,
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The directory could not be read to find old log files.  
+    /// 古いログ・ファイルを探すためのディレクトリーを読めませんでした。  
+    ReadDir {
+        path: PathBuf,
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The old log file could not be deleted.  
+    /// 古いログ・ファイルを削除できませんでした。  
+    Remove {
+        path: PathBuf,
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The rotated log file could not be compressed. The original file remains.  
+    /// ローテーションしたログ・ファイルを圧縮できませんでした。元のファイルは残ります。  
+    Compress {
+        path: PathBuf,
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The file name pattern is invalid.  
+    /// ファイル名のパターンが不正です。  
+    Pattern(String),
+    /// A thread panicked while holding the lock.  
+    /// ロックを持ったスレッドがパニックしました。  
+    Poisoned(String),
+    /// The configuration is invalid.  
+    /// 設定が不正です。  
+    Config(String),
+    /// The flush at exit could not be registered. The return value of `atexit`.  
+    /// 終了時のフラッシュを登録できませんでした。 `atexit` の戻り値。  
+    AtExit(i32),
 }
-impl ReserveTarget {
-    fn is_t(self) -> bool {
-        self.target
-    }
-    fn switch(&mut self) {
-        self.target = !self.target;
-    }
+
+/// Settings applied at once by `Log::apply_config()`.  
+/// Only the settings that are set are changed.  
+/// `Log::apply_config()` で一度に適用する設定です。  
+/// 設定したものだけが変更されます。  
+///
+/// # Examples
+///
+/// ```no_run
+/// use casual_logger::{Level, Log, LogConfig, Opt};
+///
+/// let mut config = LogConfig::new();
+/// config
+///     .file_name("tic-tac-toe")
+///     .dir("logs")
+///     .level(Level::Info)
+///     .retention_days(2)
+///     .opt(Opt::Release);
+/// if let Err(e) = Log::apply_config(&config) {
+///     println!("{}", e);
+/// }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct LogConfig {
+    /// Log file name prefix.  
+    /// ログ・ファイル名接頭辞。  
+    file_name: Option<(String, bool)>,
+    /// Log file extension.  
+    /// ログ・ファイルの拡張子。  
+    file_ext: Option<(String, bool)>,
+    /// Log level.  
+    /// ログ・レベル。  
+    level: Option<(Level, bool)>,
+    /// Retention days.  
+    /// 保持日数。  
+    retention_days: Option<(i64, bool)>,
+    /// Maximum number of log files. 0 is unlimited.  
+    /// ログ・ファイルの最大数。 0 は無制限。  
+    retention_files: Option<(usize, bool)>,
+    /// Maximum total size of log files in bytes. 0 is unlimited.  
+    /// ログ・ファイルの合計サイズの上限（バイト）。 0 は無制限。  
+    retention_size: Option<(u64, bool)>,
+    /// Timeout seconds of flush.  
+    /// フラッシュのタイムアウト秒。  
+    timeout_secs: Option<(u64, bool)>,
+    /// Directory where log files are placed.  
+    /// ログ・ファイルを置くディレクトリー。  
+    dir: Option<(String, bool)>,
+    /// Maximum file size in bytes. 0 is unlimited.  
+    /// ファイル・サイズの上限（バイト）。 0 は無制限。  
+    max_file_size: Option<(u64, bool)>,
+    /// Rotation period.  
+    /// ローテーション周期。  
+    rotation: Option<(Rotation, bool)>,
+    /// Compress rotated files.  
+    /// ローテーションされたファイルを圧縮します。  
+    compress: Option<(bool, bool)>,
+    /// Remove old log files each time a file is opened.  
+    /// ファイルを開くたびに古いログ・ファイルを削除します。  
+    auto_remove: Option<(bool, bool)>,
+    /// What to do with the log while the file cannot be written.  
+    /// ファイルに書けない間、ログをどうするか。  
+    fallback: Option<(Fallback, bool)>,
+    /// Maximum number of logs waiting to be written. 0 is unlimited.  
+    /// 書き込みを待つログの最大数。 0 は無制限。  
+    queue_capacity: Option<(usize, bool)>,
+    /// What to do when the queue is full.  
+    /// キューが満杯のときにどうするか。  
+    overflow: Option<(Overflow, bool)>,
+    /// Synchronize the file after writing a log at or above this level.  
+    /// このレベル以上のログを書いた後、ファイルを同期します。  
+    sync_level: Option<(Option<Level>, bool)>,
+    /// How to write the log.  
+    /// ログをどう書き込むか。  
+    write_mode: Option<(WriteMode, bool)>,
+    /// Levels per module or target.  
+    /// モジュールまたはターゲットごとのレベル。  
+    level_directives: Option<(LevelDirectives, bool)>,
+    /// Optimization.  
+    /// 最適化。  
+    opt: Option<(Opt, bool)>,
 }
 
-struct SignalCanFlush {
-    can_flush: bool,
+/// What to do with the log while the file cannot be written.  
+/// The file is retried with increasing intervals, and logging  
+/// recovers once the file becomes writable.  
+/// ファイルに書けない間、ログをどうするかです。  
+/// ファイルは間隔を広げながら再試行し、  
+/// 書けるようになればロギングは回復します。  
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fallback {
+    /// Discard the log.  
+    /// ログを捨てます。  
+    Discard,
+    /// Write the log to the standard error output.  
+    /// ログを標準エラー出力に書きます。  
+    Stderr,
+    /// Keep the log in memory up to this number of bytes,  
+    /// and write it when the file becomes writable. The oldest is discarded first.  
+    /// このバイト数までログをメモリーに取っておき、  
+    /// ファイルに書けるようになったら書きます。古いものから捨てます。  
+    Memory(usize),
 }
-impl Default for SignalCanFlush {
-    fn default() -> Self {
-        SignalCanFlush { can_flush: true }
-    }
+
+/// What to do when the queue of logs waiting to be written is full.  
+/// See also: `Log::set_queue_capacity()`.  
+/// 書き込みを待つログのキューが満杯のときにどうするかです。  
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// The caller waits until there is space.  
+    /// 空きができるまで呼び出し元が待ちます。  
+    Block,
+    /// Drop the new log.  
+    /// 新しいログを捨てます。  
+    DropNewest,
+    /// Drop the oldest log in the queue.  
+    /// キューの中で一番古いログを捨てます。  
+    DropOldest,
+    /// Drop the new log if it is less important than this level,  
+    /// otherwise the caller waits.  
+    /// 新しいログがこのレベルより重要でなければ捨て、  
+    /// そうでなければ呼び出し元が待ちます。  
+    DropBelow(Level),
 }
-impl SignalCanFlush {
-    pub fn can_flush(&self) -> bool {
-        self.can_flush
-    }
-    pub fn set_can_flush(&mut self, val: bool) {
-        self.can_flush = val;
+
+/// Why the log file is deleted.  
+/// ログ・ファイルが削除される理由です。  
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoveReason {
+    /// Older than the retention days.  
+    /// 保持日数より古い。  
+    RetentionDays,
+    /// Over the retention files.  
+    /// ファイル保持数を超えている。  
+    RetentionFiles,
+    /// Over the retention size.  
+    /// ファイル保持サイズを超えている。  
+    RetentionSize,
+}
+impl fmt::Display for RemoveReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RemoveReason::RetentionDays => write!(f, "Older than the retention days"),
+            RemoveReason::RetentionFiles => write!(f, "Over the retention files"),
+            RemoveReason::RetentionSize => write!(f, "Over the retention size"),
+        }
     }
 }
 
-/*
-struct ParticipatingThreadsCounter {
-    /// Number of threads not yet finished.
-    thread_count: u32,
+/// Old log file to be deleted.  
+/// 削除される古いログ・ファイルです。  
+#[derive(Clone, Debug)]
+pub struct OldLog {
+    /// Path of the log file.  
+    /// ログ・ファイルのパス。  
+    pub path: PathBuf,
+    /// Why it is deleted.  
+    /// 削除される理由。  
+    pub reason: RemoveReason,
 }
-impl Default for ParticipatingThreadsCounter {
-    fn default() -> Self {
-        ParticipatingThreadsCounter { thread_count: 0 }
+impl OldLog {
+    fn new(path: PathBuf, reason: RemoveReason) -> Self {
+        OldLog { path, reason }
     }
 }
-impl ParticipatingThreadsCounter {
-    fn increase_thread_count(&mut self) {
-        self.thread_count += 1;
-    }
-    fn decrease_thread_count(&mut self) {
-        self.thread_count -= 1;
-    }
-    pub fn get_thread_count(&self) -> u32 {
-        self.thread_count
-    }
+
+/// Result of deleting old log files.  
+/// 古いログ・ファイルを削除した結果です。  
+#[derive(Clone, Debug, Default)]
+pub struct RemoveReport {
+    /// Deleted log files.  
+    /// 削除したログ・ファイル。  
+    pub removed: Vec<OldLog>,
+    /// Log files that could not be deleted, and the reason.  
+    /// 削除できなかったログ・ファイルと、その理由。  
+    pub failed: Vec<(OldLog, Error)>,
+}
+
+/// Flushes the log when dropped. See Log::init_guard().  
+/// ドロップされるとログをフラッシュします。 Log::init_guard() を参照。  
+#[must_use = "The log is flushed when the guard is dropped. Bind it, e.g. `let _guard = ...`."]
+pub struct FlushGuard {}
+
+/// Levels per module or target, such as "mygame::ai=Trace,mygame::net=Warn".  
+/// Create it with `str::parse()`. See also: `Log::set_level_directives()`.  
+/// モジュールまたはターゲットごとのレベル。 "mygame::ai=Trace,mygame::net=Warn" など。  
+/// `str::parse()` で作ってください。  
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LevelDirectives {
+    /// Module and level. The longest module comes first.  
+    /// モジュールとレベル。一番長いモジュールが先です。  
+    directives: Vec<(String, Level)>,
+    /// The most verbose level number of the directives. 0 if none.  
+    /// 指定の中で一番詳しいレベルの番号。無ければ 0 。  
+    max_number: usize,
+}
+
+/// A value that the logging macros can put into a `Table`, such as `&str`, `i32`, `f64` or `bool`.  
+/// ロギング・マクロが `Table` に入れられる値。 `&str` 、 `i32` 、 `f64` 、 `bool` など。  
+pub trait TableValue {
+    /// Insert this value with the key.  
+    /// この値をキーで挿入します。  
+    fn insert_into(&self, table: &mut Table, key: &str);
+}
+
+/// A `tracing_subscriber::Layer` that writes events with `Log::`. Requires the `tracing` feature.  
+/// The fields of an event are keys, and its "message" field is the message.  
+/// The fields of the spans are sub tables named after the spans, nested as the spans are.  
+/// The target, module path, file and line are written as the keys  
+/// "Target", "ModulePath", "File" and "Line".  
+/// イベントを `Log::` で書く `tracing_subscriber::Layer` 。 `tracing` フィーチャーが必要です。  
+/// イベントのフィールドはキーで、 "message" フィールドはメッセージです。  
+/// スパンのフィールドはスパン名のサブ・テーブルで、スパンと同じように入れ子になります。  
+/// ターゲット、モジュール・パス、ファイル、行は、  
+/// キー "Target" 、 "ModulePath" 、 "File" 、 "Line" として書きます。  
+///
+/// ```no_run
+/// use casual_logger::TracingLayer;
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry().with(TracingLayer::new()).init();
+/// ```
+#[cfg(feature = "tracing")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingLayer {}
+
+/// How to write the log.  
+/// ログをどう書き込むかです。  
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Logging returns at once, and the log is written by the writer thread.  
+    /// Don't forget `Log::flush()`.  
+    /// ロギングはすぐに戻り、ログは書き込みスレッドが書きます。  
+    /// `Log::flush()` を忘れないでください。  
+    Background,
+    /// The log is handed to the OS on the calling thread before logging returns.  
+    /// It is slower, but there is no thread and nothing to flush.  
+    /// Suitable for short-lived command line tools.  
+    /// It is not synchronized to the disk. See also: `Log::set_sync_level()`.  
+    /// ロギングから戻る前に、呼び出し元のスレッドでログを OS に渡します。  
+    /// 遅くなりますが、スレッドはなく、フラッシュするものもありません。  
+    /// 短命なコマンドライン・ツールに向いています。  
+    /// ディスクへは同期しません。 `Log::set_sync_level()` も見てください。  
+    Synchronous,
 }
-*/
 
 /// Optimization.  
 /// 最適化。  
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Opt {
     /// Displays the work running in the background to standard output.  
     /// バックグラウンドで実行中の作業を標準出力に表示します。  
@@ -1174,28 +1687,29 @@ pub enum Opt {
     Release,
 }
 
-/// Optimization.
-struct OptState {
-    /// The optimization cannot be changed later.  
-    /// 最適化は後で変更できません。  
-    opt_important: bool,
-    /// Optimization.  
-    /// 最適化。  
-    opt: Opt,
-}
-impl Default for OptState {
-    fn default() -> Self {
-        OptState {
-            opt_important: false,
-            opt: Opt::BeginnersSupport,
+impl FromStr for Opt {
+    type Err = Error;
+    /// The name, such as "Release".  
+    /// "Release" などの名前。  
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Development" => Ok(Opt::Development),
+            "BeginnersSupport" => Ok(Opt::BeginnersSupport),
+            "Release" => Ok(Opt::Release),
+            _ => Err(Error::Config(format!("Unknown optimization. Opt=|{}|", s))),
         }
     }
 }
-impl OptState {
-    fn get(&self) -> Opt {
-        self.opt
-    }
-    fn set(&mut self, val: Opt) {
-        self.opt = val;
+
+/// The value of the environment variable, if set and not empty.  
+/// 環境変数の値。設定されていて空でなければ。  
+fn env_var(name: &str) -> Option<String> {
+    match std::env::var(name) {
+        Ok(value) if !value.is_empty() => Some(value),
+        _ => None,
     }
 }
+
+fn env_error(name: &str, value: &str) -> Error {
+    Error::Config(format!("{}=|{}| is ignored.", name, value))
+}
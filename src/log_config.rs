@@ -0,0 +1,778 @@
+//! Settings applied at once.  
+//! 一度に適用する設定。  
+use crate::logger::Logger;
+use crate::{
+    env_error, env_var, Error, Extension, Fallback, Level, LevelDirectives, LogConfig, Opt,
+    Overflow, Rotation, WriteMode, ENV_DIR, ENV_LEVEL, ENV_LEVEL_DIRECTIVES, ENV_OPT,
+    ENV_RETENTION_DAYS,
+};
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+impl LogConfig {
+    /// Nothing is set yet.  
+    /// まだ何も設定していません。  
+    pub fn new() -> Self {
+        LogConfig::default()
+    }
+
+    /// Log file name prefix. See also: `Log::set_file_name()`.  
+    /// ログ・ファイル名接頭辞。  
+    pub fn file_name(&mut self, value: &str) -> &mut Self {
+        self.file_name = Some((value.to_string(), false));
+        self
+    }
+    /// Log file name prefix. It cannot be changed later.  
+    /// ログ・ファイル名接頭辞。後で変更できません。  
+    pub fn file_name_important(&mut self, value: &str) -> &mut Self {
+        self.file_name = Some((value.to_string(), true));
+        self
+    }
+
+    /// Log file extension. See also: `Log::set_file_ext()`.  
+    /// ログ・ファイルの拡張子。  
+    pub fn file_ext(&mut self, value: Extension) -> &mut Self {
+        self.file_ext = Some((LogConfig::ext_str(value).to_string(), false));
+        self
+    }
+    /// Log file extension. It cannot be changed later.  
+    /// ログ・ファイルの拡張子。後で変更できません。  
+    pub fn file_ext_important(&mut self, value: Extension) -> &mut Self {
+        self.file_ext = Some((LogConfig::ext_str(value).to_string(), true));
+        self
+    }
+
+    /// Log level. See also: `Log::set_level()`.  
+    /// ログ・レベル。  
+    pub fn level(&mut self, value: Level) -> &mut Self {
+        self.level = Some((value, false));
+        self
+    }
+    /// Log level. It cannot be changed later.  
+    /// ログ・レベル。後で変更できません。  
+    pub fn level_important(&mut self, value: Level) -> &mut Self {
+        self.level = Some((value, true));
+        self
+    }
+
+    /// Retention days. See also: `Log::set_retention_days()`.  
+    /// 保持日数。  
+    pub fn retention_days(&mut self, value: i64) -> &mut Self {
+        self.retention_days = Some((value, false));
+        self
+    }
+    /// Retention days. It cannot be changed later.  
+    /// 保持日数。後で変更できません。  
+    pub fn retention_days_important(&mut self, value: i64) -> &mut Self {
+        self.retention_days = Some((value, true));
+        self
+    }
+
+    /// Maximum number of log files. 0 is unlimited. See also: `Log::set_retention_files()`.  
+    /// ログ・ファイルの最大数。 0 は無制限。  
+    pub fn retention_files(&mut self, value: usize) -> &mut Self {
+        self.retention_files = Some((value, false));
+        self
+    }
+    /// Maximum number of log files. 0 is unlimited. It cannot be changed later.  
+    /// ログ・ファイルの最大数。 0 は無制限。後で変更できません。  
+    pub fn retention_files_important(&mut self, value: usize) -> &mut Self {
+        self.retention_files = Some((value, true));
+        self
+    }
+
+    /// Maximum total size of log files in bytes. 0 is unlimited. See also: `Log::set_retention_size()`.  
+    /// ログ・ファイルの合計サイズの上限（バイト）。 0 は無制限。  
+    pub fn retention_size(&mut self, value: u64) -> &mut Self {
+        self.retention_size = Some((value, false));
+        self
+    }
+    /// Maximum total size of log files in bytes. 0 is unlimited. It cannot be changed later.  
+    /// ログ・ファイルの合計サイズの上限（バイト）。 0 は無制限。後で変更できません。  
+    pub fn retention_size_important(&mut self, value: u64) -> &mut Self {
+        self.retention_size = Some((value, true));
+        self
+    }
+
+    /// Timeout seconds of flush. See also: `Log::set_timeout_secs()`.  
+    /// フラッシュのタイムアウト秒。  
+    pub fn timeout_secs(&mut self, value: u64) -> &mut Self {
+        self.timeout_secs = Some((value, false));
+        self
+    }
+    /// Timeout seconds of flush. It cannot be changed later.  
+    /// フラッシュのタイムアウト秒。後で変更できません。  
+    pub fn timeout_secs_important(&mut self, value: u64) -> &mut Self {
+        self.timeout_secs = Some((value, true));
+        self
+    }
+
+    /// Directory where log files are placed. See also: `Log::set_dir()`.  
+    /// ログ・ファイルを置くディレクトリー。  
+    pub fn dir(&mut self, value: &str) -> &mut Self {
+        self.dir = Some((value.to_string(), false));
+        self
+    }
+    /// Directory where log files are placed. It cannot be changed later.  
+    /// ログ・ファイルを置くディレクトリー。後で変更できません。  
+    pub fn dir_important(&mut self, value: &str) -> &mut Self {
+        self.dir = Some((value.to_string(), true));
+        self
+    }
+
+    /// Maximum file size in bytes. 0 is unlimited. See also: `Log::set_max_file_size()`.  
+    /// ファイル・サイズの上限（バイト）。 0 は無制限。  
+    pub fn max_file_size(&mut self, value: u64) -> &mut Self {
+        self.max_file_size = Some((value, false));
+        self
+    }
+    /// Maximum file size in bytes. 0 is unlimited. It cannot be changed later.  
+    /// ファイル・サイズの上限（バイト）。 0 は無制限。後で変更できません。  
+    pub fn max_file_size_important(&mut self, value: u64) -> &mut Self {
+        self.max_file_size = Some((value, true));
+        self
+    }
+
+    /// Rotation period. See also: `Log::set_rotation()`.  
+    /// ローテーション周期。  
+    pub fn rotation(&mut self, value: Rotation) -> &mut Self {
+        self.rotation = Some((value, false));
+        self
+    }
+    /// Rotation period. It cannot be changed later.  
+    /// ローテーション周期。後で変更できません。  
+    pub fn rotation_important(&mut self, value: Rotation) -> &mut Self {
+        self.rotation = Some((value, true));
+        self
+    }
+
+    /// Compress rotated files. See also: `Log::set_compress()`.  
+    /// ローテーションされたファイルを圧縮します。  
+    pub fn compress(&mut self, value: bool) -> &mut Self {
+        self.compress = Some((value, false));
+        self
+    }
+    /// Compress rotated files. It cannot be changed later.  
+    /// ローテーションされたファイルを圧縮します。後で変更できません。  
+    pub fn compress_important(&mut self, value: bool) -> &mut Self {
+        self.compress = Some((value, true));
+        self
+    }
+
+    /// Remove old log files each time a file is opened. See also: `Log::set_auto_remove()`.  
+    /// ファイルを開くたびに古いログ・ファイルを削除します。  
+    pub fn auto_remove(&mut self, value: bool) -> &mut Self {
+        self.auto_remove = Some((value, false));
+        self
+    }
+    /// Remove old log files each time a file is opened. It cannot be changed later.  
+    /// ファイルを開くたびに古いログ・ファイルを削除します。後で変更できません。  
+    pub fn auto_remove_important(&mut self, value: bool) -> &mut Self {
+        self.auto_remove = Some((value, true));
+        self
+    }
+
+    /// What to do with the log while the file cannot be written. See also: `Log::set_fallback()`.  
+    /// ファイルに書けない間、ログをどうするか。  
+    pub fn fallback(&mut self, value: Fallback) -> &mut Self {
+        self.fallback = Some((value, false));
+        self
+    }
+    /// What to do with the log while the file cannot be written. It cannot be changed later.  
+    /// ファイルに書けない間、ログをどうするか。後で変更できません。  
+    pub fn fallback_important(&mut self, value: Fallback) -> &mut Self {
+        self.fallback = Some((value, true));
+        self
+    }
+
+    /// Maximum number of logs waiting to be written. 0 is unlimited. See also: `Log::set_queue_capacity()`.  
+    /// 書き込みを待つログの最大数。 0 は無制限。  
+    pub fn queue_capacity(&mut self, value: usize) -> &mut Self {
+        self.queue_capacity = Some((value, false));
+        self
+    }
+    /// Maximum number of logs waiting to be written. 0 is unlimited. It cannot be changed later.  
+    /// 書き込みを待つログの最大数。 0 は無制限。後で変更できません。  
+    pub fn queue_capacity_important(&mut self, value: usize) -> &mut Self {
+        self.queue_capacity = Some((value, true));
+        self
+    }
+
+    /// What to do when the queue is full. See also: `Log::set_overflow()`.  
+    /// キューが満杯のときにどうするか。  
+    pub fn overflow(&mut self, value: Overflow) -> &mut Self {
+        self.overflow = Some((value, false));
+        self
+    }
+    /// What to do when the queue is full. It cannot be changed later.  
+    /// キューが満杯のときにどうするか。後で変更できません。  
+    pub fn overflow_important(&mut self, value: Overflow) -> &mut Self {
+        self.overflow = Some((value, true));
+        self
+    }
+
+    /// Synchronize the file after writing a log at or above this level. See also: `Log::set_sync_level()`.  
+    /// このレベル以上のログを書いた後、ファイルを同期します。  
+    pub fn sync_level(&mut self, value: Option<Level>) -> &mut Self {
+        self.sync_level = Some((value, false));
+        self
+    }
+    /// Synchronize the file after writing a log at or above this level. It cannot be changed later.  
+    /// このレベル以上のログを書いた後、ファイルを同期します。後で変更できません。  
+    pub fn sync_level_important(&mut self, value: Option<Level>) -> &mut Self {
+        self.sync_level = Some((value, true));
+        self
+    }
+
+    /// How to write the log. See also: `Log::set_write_mode()`.  
+    /// ログをどう書き込むか。  
+    pub fn write_mode(&mut self, value: WriteMode) -> &mut Self {
+        self.write_mode = Some((value, false));
+        self
+    }
+    /// How to write the log. It cannot be changed later.  
+    /// ログをどう書き込むか。後で変更できません。  
+    pub fn write_mode_important(&mut self, value: WriteMode) -> &mut Self {
+        self.write_mode = Some((value, true));
+        self
+    }
+
+    /// Levels per module or target. See also: `Log::set_level_directives()`.  
+    /// モジュールまたはターゲットごとのレベル。  
+    pub fn level_directives(&mut self, value: LevelDirectives) -> &mut Self {
+        self.level_directives = Some((value, false));
+        self
+    }
+    /// Levels per module or target. It cannot be changed later.  
+    /// モジュールまたはターゲットごとのレベル。後で変更できません。  
+    pub fn level_directives_important(&mut self, value: LevelDirectives) -> &mut Self {
+        self.level_directives = Some((value, true));
+        self
+    }
+
+    /// Optimization. See also: `Log::set_opt()`.  
+    /// 最適化。  
+    pub fn opt(&mut self, value: Opt) -> &mut Self {
+        self.opt = Some((value, false));
+        self
+    }
+    /// Optimization. It cannot be changed later.  
+    /// 最適化。後で変更できません。  
+    pub fn opt_important(&mut self, value: Opt) -> &mut Self {
+        self.opt = Some((value, true));
+        self
+    }
+
+    /// Read the settings from a TOML file. See also: `Log::load_config()`.  
+    /// TOML ファイルから設定を読みます。  
+    pub fn load(path: &str) -> Result<Self, Error> {
+        match fs::read_to_string(path) {
+            Ok(text) => LogConfig::from_toml(&text),
+            Err(e) => Err(Error::open(Path::new(path), &e)),
+        }
+    }
+
+    /// Read the settings from a TOML text.  
+    /// Unknown keys are an error.  
+    /// TOML テキストから設定を読みます。  
+    /// 未知のキーはエラーです。  
+    pub fn from_toml(text: &str) -> Result<Self, Error> {
+        let document = match text.parse::<::toml::Value>() {
+            Ok(document) => document,
+            Err(e) => return Err(Error::Config(format!("TOML parse error. {}", e))),
+        };
+        let table = match document.as_table() {
+            Some(table) => table,
+            None => return Err(Error::Config("TOML must be a table.".to_string())),
+        };
+        let mut config = LogConfig::new();
+        let mut unknown_keys = Vec::new();
+        for (key, value) in table {
+            let (name, important) = if key.ends_with("_important") {
+                (&key[..key.len() - "_important".len()], true)
+            } else {
+                (key.as_str(), false)
+            };
+            match name {
+                "file_name" => {
+                    config.file_name = Some((toml_str(key, value)?.to_string(), important));
+                }
+                "file_ext" => {
+                    let ext = match toml_str(key, value)? {
+                        "Log" => Extension::Log,
+                        "LogToml" => Extension::LogToml,
+                        s => return Err(unknown_value(key, s)),
+                    };
+                    config.file_ext = Some((LogConfig::ext_str(ext).to_string(), important));
+                }
+                "level" => config.level = Some((toml_str(key, value)?.parse()?, important)),
+                "retention_days" => {
+                    config.retention_days = Some((toml_int(key, value)?, important))
+                }
+                "retention_files" => {
+                    config.retention_files = Some((toml_uint(key, value)? as usize, important));
+                }
+                "retention_size" => {
+                    config.retention_size = Some((toml_uint(key, value)?, important))
+                }
+                "timeout_secs" => config.timeout_secs = Some((toml_uint(key, value)?, important)),
+                "dir" => config.dir = Some((toml_str(key, value)?.to_string(), important)),
+                "max_file_size" => config.max_file_size = Some((toml_uint(key, value)?, important)),
+                "rotation" => {
+                    let rotation = match toml_call(toml_str(key, value)?) {
+                        ("Hourly", None) => Rotation::Hourly,
+                        ("Daily", None) => Rotation::Daily,
+                        ("Weekly", None) => Rotation::Weekly,
+                        ("Interval", Some(secs)) => match secs.parse::<i64>() {
+                            Ok(secs) => Rotation::Interval(chrono::Duration::seconds(secs)),
+                            Err(_) => return Err(unknown_value(key, secs)),
+                        },
+                        _ => return Err(unknown_value(key, toml_str(key, value)?)),
+                    };
+                    config.rotation = Some((rotation, important));
+                }
+                "compress" => config.compress = Some((toml_bool(key, value)?, important)),
+                "auto_remove" => config.auto_remove = Some((toml_bool(key, value)?, important)),
+                "fallback" => {
+                    let fallback = match toml_call(toml_str(key, value)?) {
+                        ("Discard", None) => Fallback::Discard,
+                        ("Stderr", None) => Fallback::Stderr,
+                        ("Memory", Some(bytes)) => match bytes.parse::<usize>() {
+                            Ok(bytes) => Fallback::Memory(bytes),
+                            Err(_) => return Err(unknown_value(key, bytes)),
+                        },
+                        _ => return Err(unknown_value(key, toml_str(key, value)?)),
+                    };
+                    config.fallback = Some((fallback, important));
+                }
+                "queue_capacity" => {
+                    config.queue_capacity = Some((toml_uint(key, value)? as usize, important));
+                }
+                "overflow" => {
+                    let overflow = match toml_call(toml_str(key, value)?) {
+                        ("Block", None) => Overflow::Block,
+                        ("DropNewest", None) => Overflow::DropNewest,
+                        ("DropOldest", None) => Overflow::DropOldest,
+                        ("DropBelow", Some(level)) => Overflow::DropBelow(level.parse()?),
+                        _ => return Err(unknown_value(key, toml_str(key, value)?)),
+                    };
+                    config.overflow = Some((overflow, important));
+                }
+                "sync_level" => {
+                    let sync_level = match toml_str(key, value)? {
+                        "None" => None,
+                        s => Some(s.parse()?),
+                    };
+                    config.sync_level = Some((sync_level, important));
+                }
+                "write_mode" => {
+                    let write_mode = match toml_str(key, value)? {
+                        "Background" => WriteMode::Background,
+                        "Synchronous" => WriteMode::Synchronous,
+                        s => return Err(unknown_value(key, s)),
+                    };
+                    config.write_mode = Some((write_mode, important));
+                }
+                "level_directives" => {
+                    config.level_directives = Some((toml_str(key, value)?.parse()?, important));
+                }
+                "opt" => config.opt = Some((toml_str(key, value)?.parse()?, important)),
+                _ => unknown_keys.push(key.to_string()),
+            }
+        }
+        if !unknown_keys.is_empty() {
+            return Err(Error::Config(format!(
+                "Unknown key(s). keys=|{}|",
+                unknown_keys.join(", ")
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Read `ENV_LEVEL`, `ENV_LEVEL_DIRECTIVES`, `ENV_OPT`, `ENV_DIR` and `ENV_RETENTION_DAYS` as important.  
+    /// Invalid ones are skipped, and returned as errors.  
+    /// `ENV_LEVEL` 、 `ENV_LEVEL_DIRECTIVES` 、 `ENV_OPT` 、 `ENV_DIR` 、 `ENV_RETENTION_DAYS` を重要として読みます。  
+    /// 不正なものは飛ばして、エラーとして返します。  
+    pub(crate) fn from_env() -> (Self, Vec<Error>) {
+        LogConfig::from_vars(env_var)
+    }
+
+    /// Same as `LogConfig::from_env()`, with the variables read by `env_var`.  
+    /// It returns None if the variable is not set or empty.  
+    /// `LogConfig::from_env()` と同じで、変数は `env_var` で読みます。  
+    /// 変数が設定されていないか空なら None を返します。  
+    fn from_vars<F>(env_var: F) -> (Self, Vec<Error>)
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut config = LogConfig::new();
+        let mut errors = Vec::new();
+        if let Some(value) = env_var(ENV_LEVEL) {
+            match value.parse() {
+                Ok(level) => config.level = Some((level, true)),
+                Err(_) => errors.push(env_error(ENV_LEVEL, &value)),
+            }
+        }
+        if let Some(value) = env_var(ENV_LEVEL_DIRECTIVES) {
+            match value.parse() {
+                Ok(directives) => config.level_directives = Some((directives, true)),
+                Err(_) => errors.push(env_error(ENV_LEVEL_DIRECTIVES, &value)),
+            }
+        }
+        if let Some(value) = env_var(ENV_OPT) {
+            match value.parse() {
+                Ok(opt) => config.opt = Some((opt, true)),
+                Err(_) => errors.push(env_error(ENV_OPT, &value)),
+            }
+        }
+        if let Some(value) = env_var(ENV_DIR) {
+            config.dir = Some((value, true));
+        }
+        if let Some(value) = env_var(ENV_RETENTION_DAYS) {
+            match value.parse::<i64>() {
+                // Negative days are accepted, as `Log::set_retention_days()` does.
+                // 負の日数も `Log::set_retention_days()` と同じく受け付けます。
+                Ok(days) => config.retention_days = Some((days, true)),
+                Err(_) => errors.push(env_error(ENV_RETENTION_DAYS, &value)),
+            }
+        }
+        (config, errors)
+    }
+
+    fn ext_str(ext: Extension) -> &'static str {
+        match ext {
+            Extension::LogToml => ".log.toml",
+            Extension::Log => ".log",
+        }
+    }
+
+    /// Check the settings. Nothing is changed, and no file or directory is created.  
+    /// Values accepted by `Log::set_xxx()` are accepted.  
+    /// A negative `retention_days` and a `Rotation::Interval` of less than 1 second are valid.  
+    /// 設定を検査します。何も変更せず、ファイルもディレクトリーも作りません。  
+    /// `Log::set_xxx()` が受け付ける値は受け付けます。  
+    /// 負の `retention_days` と、1秒未満の `Rotation::Interval` は正しい値です。  
+    pub fn validate(&self) -> Result<(), Error> {
+        if let Some((file_name, _)) = &self.file_name {
+            if file_name.is_empty() || file_name.contains(&['/', '\\'][..]) {
+                return Err(Error::Config(format!(
+                    "file_name must be a non-empty name without a path separator. file_name=|{}|",
+                    file_name
+                )));
+            }
+        }
+        if let Some((capacity, _)) = self.queue_capacity {
+            if self.overflow.is_some() && capacity < 1 {
+                return Err(Error::Config(
+                    "overflow has no effect with queue_capacity 0 (Unlimited).".to_string(),
+                ));
+            }
+            if let Some((WriteMode::Synchronous, _)) = self.write_mode {
+                if 0 < capacity {
+                    return Err(Error::Config(
+                        "queue_capacity has no effect with WriteMode::Synchronous.".to_string(),
+                    ));
+                }
+            }
+        }
+        if let Some((dir, _)) = &self.dir {
+            if dir.is_empty() {
+                return Err(Error::Config("dir must not be empty.".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Assign the settings.  
+    /// Settings already set with `_important` are not changed.  
+    /// 設定を代入します。  
+    /// `_important` で設定済みのものは変更しません。  
+    ///
+    /// # Returns
+    ///
+    /// True if the path of the log file changed. Then close the file.  
+    /// ログ・ファイルのパスが変わったら true 。そのときはファイルを閉じてください。  
+    pub(crate) fn assign_to(&self, logger: &mut Logger) -> bool {
+        let old_path = logger.path_settings();
+        assign(
+            &self.file_name,
+            &mut logger.file_name_important,
+            &mut logger.file_prefix,
+        );
+        assign(
+            &self.file_ext,
+            &mut logger.file_ext_important,
+            &mut logger.file_extension,
+        );
+        assign(&self.level, &mut logger.level_important, &mut logger.level);
+        assign(
+            &self.retention_days,
+            &mut logger.retention_days_important,
+            &mut logger.retention_days,
+        );
+        assign(
+            &self.retention_files,
+            &mut logger.retention_files_important,
+            &mut logger.retention_files,
+        );
+        assign(
+            &self.retention_size,
+            &mut logger.retention_size_important,
+            &mut logger.retention_size,
+        );
+        assign(
+            &self.timeout_secs,
+            &mut logger.timeout_secs_important,
+            &mut logger.timeout_secs,
+        );
+        assign(&self.dir, &mut logger.dir_important, &mut logger.dir);
+        assign(
+            &self.max_file_size,
+            &mut logger.max_file_size_important,
+            &mut logger.max_file_size,
+        );
+        assign(
+            &self.rotation,
+            &mut logger.rotation_important,
+            &mut logger.rotation,
+        );
+        assign(
+            &self.compress,
+            &mut logger.compress_important,
+            &mut logger.compress,
+        );
+        assign(
+            &self.auto_remove,
+            &mut logger.auto_remove_important,
+            &mut logger.auto_remove,
+        );
+        assign(
+            &self.fallback,
+            &mut logger.fallback_important,
+            &mut logger.fallback,
+        );
+        assign(
+            &self.queue_capacity,
+            &mut logger.queue_capacity_important,
+            &mut logger.queue_capacity,
+        );
+        assign(
+            &self.overflow,
+            &mut logger.overflow_important,
+            &mut logger.overflow,
+        );
+        assign(
+            &self.sync_level,
+            &mut logger.sync_level_important,
+            &mut logger.sync_level,
+        );
+        assign(
+            &self.write_mode,
+            &mut logger.write_mode_important,
+            &mut logger.write_mode,
+        );
+        assign(
+            &self.level_directives,
+            &mut logger.level_directives_important,
+            &mut logger.level_directives,
+        );
+        assign(&self.opt, &mut logger.opt_important, &mut logger.opt);
+        old_path != logger.path_settings()
+    }
+}
+
+/// Same as `Log::set_xxx()` and `Log::set_xxx_important()`.  
+fn assign<T: Clone>(setting: &Option<(T, bool)>, important: &mut bool, value: &mut T) {
+    if let Some((new_value, new_important)) = setting {
+        if !*important {
+            *value = new_value.clone();
+        }
+        if *new_important {
+            *important = true;
+        }
+    }
+}
+
+fn toml_str<'a>(key: &str, value: &'a ::toml::Value) -> Result<&'a str, Error> {
+    match value.as_str() {
+        Some(s) => Ok(s),
+        None => Err(Error::Config(format!("{} must be a string.", key))),
+    }
+}
+
+fn toml_int(key: &str, value: &::toml::Value) -> Result<i64, Error> {
+    match value.as_integer() {
+        Some(n) => Ok(n),
+        None => Err(Error::Config(format!("{} must be an integer.", key))),
+    }
+}
+
+fn toml_uint(key: &str, value: &::toml::Value) -> Result<u64, Error> {
+    match u64::try_from(toml_int(key, value)?) {
+        Ok(n) => Ok(n),
+        Err(_) => Err(Error::Config(format!("{} must not be negative.", key))),
+    }
+}
+
+fn toml_bool(key: &str, value: &::toml::Value) -> Result<bool, Error> {
+    match value.as_bool() {
+        Some(b) => Ok(b),
+        None => Err(Error::Config(format!("{} must be true or false.", key))),
+    }
+}
+
+/// "DropBelow(Warn)" to ("DropBelow", Some("Warn")).  
+/// "DropBelow(Warn)" を ("DropBelow", Some("Warn")) にします。  
+fn toml_call(s: &str) -> (&str, Option<&str>) {
+    match (s.find('('), s.ends_with(')')) {
+        (Some(open), true) => (&s[..open], Some(s[open + 1..s.len() - 1].trim())),
+        _ => (s, None),
+    }
+}
+
+fn unknown_value(key: &str, value: &str) -> Error {
+    Error::Config(format!("Unknown value. {}=|{}|", key, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Error, Level, LogConfig, LogHandle, Opt, Overflow, Rotation, ENV_DIR, ENV_LEVEL,
+        ENV_LEVEL_DIRECTIVES, ENV_OPT, ENV_RETENTION_DAYS,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn validate_accepts_what_the_setters_accept() {
+        let mut config = LogConfig::new();
+        config
+            .retention_days(-1)
+            .rotation(Rotation::Interval(chrono::Duration::zero()));
+        assert!(config.validate().is_ok());
+
+        let mut config = LogConfig::new();
+        config.file_name("nested/name");
+        assert!(config.validate().is_err());
+        let mut config = LogConfig::new();
+        config.dir("");
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_does_not_create_the_directory() {
+        let dir =
+            std::env::temp_dir().join(format!("casual_logger-validate-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut config = LogConfig::new();
+        config.dir(&dir.to_string_lossy());
+        assert!(config.validate().is_ok());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn from_toml_reads_values_and_important_keys() {
+        let config = LogConfig::from_toml(
+            r#"
+            level_important = "Info"
+            retention_days = -1
+            rotation = "Interval(1800)"
+            overflow = "DropBelow(Warn)"
+            sync_level = "None"
+            opt = "Release"
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(config.level, Some((Level::Info, true))));
+        assert!(matches!(config.retention_days, Some((-1, false))));
+        match config.rotation {
+            Some((Rotation::Interval(interval), false)) => assert_eq!(interval.num_seconds(), 1800),
+            _ => panic!("{:?}", config.rotation),
+        }
+        assert!(matches!(
+            config.overflow,
+            Some((Overflow::DropBelow(Level::Warn), false))
+        ));
+        assert!(matches!(config.sync_level, Some((None, false))));
+        assert!(matches!(config.opt, Some((Opt::Release, false))));
+        assert!(config.file_name.is_none());
+    }
+
+    #[test]
+    fn from_toml_names_all_the_unknown_keys() {
+        match LogConfig::from_toml("level = \"Info\"\nlevle = \"Info\"\ndirectory = \"logs\"") {
+            Err(Error::Config(message)) => {
+                assert!(message.contains("levle"), "{}", message);
+                assert!(message.contains("directory"), "{}", message);
+            }
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn from_toml_rejects_bad_values() {
+        for text in &[
+            "level = \"Loud\"",
+            "level = 3",
+            "retention_files = -1",
+            "compress = \"yes\"",
+            "rotation = \"Interval(soon)\"",
+            "fallback = \"Memory\"",
+            "overflow = \"DropBelow(Loud)\"",
+            "write_mode = \"Later\"",
+            "not toml",
+            "level = [",
+        ] {
+            assert!(LogConfig::from_toml(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn environment_variables_win_over_the_code_and_the_file() {
+        let temp = |name: &str| {
+            std::env::temp_dir()
+                .join(format!(
+                    "casual_logger-from_env-{}-{}",
+                    name,
+                    std::process::id()
+                ))
+                .to_string_lossy()
+                .to_string()
+        };
+        let (env_dir, file_dir) = (temp("env"), temp("file"));
+        let mut vars = HashMap::new();
+        vars.insert(ENV_LEVEL, "Warn".to_string());
+        vars.insert(ENV_OPT, "Release".to_string());
+        vars.insert(ENV_DIR, env_dir.clone());
+        vars.insert(ENV_LEVEL_DIRECTIVES, "a=Loud".to_string());
+        vars.insert(ENV_RETENTION_DAYS, "-3".to_string());
+        let (env, errors) = LogConfig::from_vars(|name| vars.get(name).cloned());
+        // The invalid one is skipped. Negative days are accepted like the setter.
+        assert_eq!(errors.len(), 1);
+        assert!(env.level_directives.is_none());
+        assert_eq!(env.retention_days, Some((-3, true)));
+
+        let log = LogHandle::new();
+        log.apply_config(&env).unwrap();
+        log.set_level(Level::Trace);
+        log.set_level_important(Level::Debug);
+        log.set_opt_important(Opt::Development);
+        log.set_retention_days(7);
+        let file = LogConfig::from_toml(&format!(
+            "level_important = \"Info\"\ndir_important = '{}'",
+            file_dir
+        ));
+        log.apply_config(&file.unwrap()).unwrap();
+        assert_eq!(log.get_level().unwrap(), Level::Warn);
+        assert_eq!(log.get_opt().unwrap(), Opt::Release);
+        assert_eq!(log.get_dir().unwrap(), env_dir);
+        assert_eq!(log.get_retention_days().unwrap(), -3);
+
+        vars.insert(ENV_RETENTION_DAYS, "soon".to_string());
+        let (env, errors) = LogConfig::from_vars(|name| vars.get(name).cloned());
+        assert_eq!(errors.len(), 2);
+        assert!(env.retention_days.is_none());
+        let _ = std::fs::remove_dir_all(&env_dir);
+        let _ = std::fs::remove_dir_all(&file_dir);
+    }
+}
@@ -0,0 +1,120 @@
+//! Backend of the `log` crate.  
+//! `log` クレートのバックエンド。  
+use crate::{Error, Level, Log, LogHandle, Table, DEFAULT_LOG};
+
+/// Receives `log::info!()` and so on, and writes them with `Log::`.  
+/// `log::info!()` などを受け取り、 `Log::` で書きます。  
+struct LogFacade;
+
+static LOG_FACADE: LogFacade = LogFacade;
+
+impl ::log::Log for LogFacade {
+    fn enabled(&self, metadata: &::log::Metadata) -> bool {
+        Log::enabled_for(to_level(metadata.level()), metadata.target())
+    }
+
+    fn log(&self, record: &::log::Record) {
+        write_record(&DEFAULT_LOG, record);
+    }
+
+    fn flush(&self) {
+        Log::flush();
+    }
+}
+
+/// Write the record as a table with the keys of the call site.  
+/// 呼び出し位置のキーを付けたテーブルとして、レコードを書きます。  
+fn write_record(log: &LogHandle, record: &::log::Record) {
+    let level = to_level(record.level());
+    if !log.enabled_for(level, record.target()) {
+        return;
+    }
+    let message = record.args().to_string();
+    let mut table = Table::default();
+    table.str("Target", record.target());
+    if let Some(module_path) = record.module_path() {
+        table.str("ModulePath", module_path);
+    }
+    if let Some(file) = record.file() {
+        table.str("File", file);
+    }
+    if let Some(line) = record.line() {
+        table.uint("Line", line as u128);
+    }
+    log.log_t(level, record.target(), &message, &mut table);
+}
+
+/// `log::Level::Info` is `Level::Info`, so `Log::set_level(Level::Notice)` hides it.  
+/// `log::Level::Info` は `Level::Info` なので、 `Log::set_level(Level::Notice)` で隠れます。  
+fn to_level(level: ::log::Level) -> Level {
+    match level {
+        ::log::Level::Error => Level::Error,
+        ::log::Level::Warn => Level::Warn,
+        ::log::Level::Info => Level::Info,
+        ::log::Level::Debug => Level::Debug,
+        ::log::Level::Trace => Level::Trace,
+    }
+}
+
+/// Set `Log::` as the logger of the `log` crate.  
+/// `Log::` を `log` クレートのロガーに設定します。  
+pub(crate) fn init() -> Result<(), Error> {
+    if let Err(e) = ::log::set_logger(&LOG_FACADE) {
+        return Err(Error::Config(format!("{}", e)));
+    }
+    // The level is checked by `Log::enabled()`, so it follows `Log::set_level()` at runtime.
+    // レベルは `Log::enabled()` で検査するので、実行中の `Log::set_level()` に従います。
+    ::log::set_max_level(::log::LevelFilter::Trace);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WriteMode;
+    use std::fs;
+
+    #[test]
+    fn records_are_written_with_their_level_target_and_call_site() {
+        let dir =
+            std::env::temp_dir().join(format!("casual_logger-log_facade-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let log = LogHandle::new();
+        log.set_dir(&dir.to_string_lossy());
+        log.set_write_mode(WriteMode::Synchronous);
+        log.set_level(Level::Info);
+        log.set_level_directives("noisy=Error".parse().unwrap());
+
+        let write = |level: ::log::Level, target: &str, message: &str| {
+            write_record(
+                &log,
+                &::log::Record::builder()
+                    .level(level)
+                    .target(target)
+                    .module_path(Some("app::net"))
+                    .file(Some("src/net.rs"))
+                    .line(Some(12))
+                    .args(format_args!("{}", message))
+                    .build(),
+            );
+        };
+        write(::log::Level::Warn, "app", "written");
+        write(::log::Level::Debug, "app", "below the level");
+        write(::log::Level::Error, "noisy::db", "over the directive");
+        write(::log::Level::Warn, "noisy", "below the directive");
+        log.flush();
+
+        let entry = fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+        let text = fs::read_to_string(entry.path()).unwrap();
+        assert!(text.contains("Warn = 'written'"), "{}", text);
+        assert!(text.contains("Target = 'app'"), "{}", text);
+        assert!(text.contains("ModulePath = 'app::net'"), "{}", text);
+        assert!(text.contains("File = 'src/net.rs'"), "{}", text);
+        assert!(text.contains("Line = 12"), "{}", text);
+        assert!(text.contains("Error = 'over the directive'"), "{}", text);
+        assert!(text.contains("Target = 'noisy::db'"), "{}", text);
+        assert!(!text.contains("below the"), "{}", text);
+        drop(log);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
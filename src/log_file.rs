@@ -1,23 +1,32 @@
 //! Used for editing and locking files.  
 //! ファイルの編集とロックに使用されます。  
-use chrono::{Date, Local};
+use chrono::{DateTime, Local};
 use std::fs::File;
+use std::path::PathBuf;
 
 /// Used for editing and locking files.  
 /// ファイルの編集とロックに使用されます。  
 pub struct LogFile {
-    /// Used for file name and deletion. Year, Month, Day.  
-    /// ファイル名と削除に使用されます。年月日。  
-    pub start_date: Date<Local>,
+    /// Used for file name and rotation. Start of the rotation period.  
+    /// ファイル名とローテーションに使用されます。ローテーション周期の開始時刻。  
+    pub start: DateTime<Local>,
+    /// Part number on the same day. The first part is 0.  
+    /// 同じ日の部番号。最初の部は 0 です。  
+    pub part: u32,
+    /// Path at the time it was opened. Settings changed later do not affect it.  
+    /// 開いた時点のパス。後で設定を変えても影響しません。  
+    pub path: PathBuf,
     /// Used for editing and locking files.  
     /// ファイルの編集とロックに使用されます。  
     pub file: File,
 }
 impl LogFile {
-    pub fn new(start_date: Date<Local>, file: File) -> Self {
+    pub fn new(start: DateTime<Local>, part: u32, path: PathBuf, file: File) -> Self {
         LogFile {
-            start_date: start_date,
-            file: file,
+            start,
+            part,
+            path,
+            file,
         }
     }
 }
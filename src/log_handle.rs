@@ -0,0 +1,1228 @@
+//! An independent logger.  
+//! 独立したロガー。  
+use crate::config_watcher;
+use crate::instance::Instance;
+use crate::logger::Logger;
+use crate::retention::Retention;
+use crate::stringifier::Stringifier;
+use crate::table::InternalTable;
+use crate::{
+    Error, Extension, Fallback, Level, LevelDirectives, Log, LogConfig, LogHandle, OldLog, Opt,
+    Overflow, RemoveReport, Rotation, Table, WriteMode, DEFAULT_OPTIMIZATION, NEW_LINE,
+};
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+
+impl Default for LogHandle {
+    fn default() -> Self {
+        LogHandle {
+            instance: Arc::new(Instance::default()),
+        }
+    }
+}
+impl LogHandle {
+    /// Create a new logger with the default settings.  
+    /// Its file, settings and writer thread are independent of `Log::`.  
+    /// デフォルトの設定で新しいロガーを作ります。  
+    /// そのファイル、設定、書き込みスレッドは `Log::` から独立しています。  
+    pub fn new() -> Self {
+        LogHandle::default()
+    }
+
+    /// The logger behind `Log::`, with the environment variables applied.  
+    /// See also: `ENV_LEVEL`.  
+    /// `Log::` の裏のロガー。環境変数を適用します。  
+    pub(crate) fn from_env() -> Self {
+        let log = LogHandle::new();
+        let (config, errors) = LogConfig::from_env();
+        if let Ok(mut logger) = log.instance.logger.lock() {
+            // No file is opened yet.
+            // まだファイルは開いていません。
+            config.assign_to(&mut logger);
+            log.instance.writer.configure(&logger);
+        }
+        for error in errors {
+            // `Log::` is not ready yet, so the callback is not called.
+            // `Log::` はまだ使えないので、コールバックは呼びません。
+            let _ = Log::record_error(&error);
+        }
+        log
+    }
+
+    /// Same as `Log::apply_config()`, for this logger.  
+    /// このロガーについて、 `Log::apply_config()` と同じです。  
+    pub fn apply_config(&self, config: &LogConfig) -> Result<(), Error> {
+        config.validate()?;
+        let path_changed = match self.instance.logger.lock() {
+            Ok(mut logger) => {
+                let path_changed = config.assign_to(&mut logger);
+                self.instance.writer.configure(&logger);
+                path_changed
+            }
+            Err(e) => return Err(Error::from(e)),
+        };
+        if path_changed {
+            // Open the new file at the next write.
+            // 次の書き込みで、新しいファイルを開きます。
+            self.close_file();
+        }
+        Ok(())
+    }
+
+    /// Same as `Log::load_config()`, for this logger.  
+    /// このロガーについて、 `Log::load_config()` と同じです。  
+    pub fn load_config(&self, path: &str) -> Result<(), Error> {
+        let config = LogConfig::load(path)?;
+        self.apply_config(&config)
+    }
+
+    /// Same as `Log::watch_config()`, for this logger.  
+    /// The watcher thread ends when this logger is dropped.  
+    /// このロガーについて、 `Log::watch_config()` と同じです。  
+    /// 見張りスレッドは、このロガーを破棄すると終わります。  
+    pub fn watch_config(&self, path: &str, interval_secs: u64) -> Result<(), Error> {
+        config_watcher::watch(self, path, interval_secs)
+    }
+
+    /// Same as `Log::unwatch_config()`, for this logger.  
+    /// このロガーについて、 `Log::unwatch_config()` と同じです。  
+    pub fn unwatch_config(&self) {
+        self.instance.watcher.stop();
+    }
+
+    /// Change the settings. Nothing is done if the lock is poisoned.  
+    /// 設定を変更します。ロックが壊れていれば何もしません。  
+    fn update<F>(&self, change: F)
+    where
+        F: FnOnce(&mut Logger),
+    {
+        if let Ok(mut logger) = self.instance.logger.lock() {
+            change(&mut logger);
+        }
+    }
+
+    /// The settings in effect.  
+    /// 有効な設定。  
+    pub(crate) fn settings(&self) -> Result<Logger, Error> {
+        self.read(|logger| logger.clone())
+    }
+
+    /// Read the settings.  
+    /// 設定を読みます。  
+    fn read<T, F>(&self, get: F) -> Result<T, Error>
+    where
+        F: FnOnce(&Logger) -> T,
+    {
+        match self.instance.logger.lock() {
+            Ok(logger) => Ok(get(&logger)),
+            Err(e) => Err(Error::from(e)),
+        }
+    }
+
+    /// Change the settings, and close the file if its path changed.  
+    /// 設定を変更し、ファイルのパスが変わったらファイルを閉じます。  
+    fn update_path<F>(&self, change: F)
+    where
+        F: FnOnce(&mut Logger),
+    {
+        let changed = match self.instance.logger.lock() {
+            Ok(mut logger) => {
+                let old_path = logger.path_settings();
+                change(&mut logger);
+                old_path != logger.path_settings()
+            }
+            Err(_) => false,
+        };
+        if changed {
+            // Open the new file at the next write.
+            // 次の書き込みで、新しいファイルを開きます。
+            self.close_file();
+        }
+    }
+
+    /// Same as `Log::set_file_name()`, for this logger.  
+    /// このロガーについて、 `Log::set_file_name()` と同じです。  
+    pub fn set_file_name(&self, prefix: &str) {
+        self.update_path(|logger| {
+            if !logger.file_name_important {
+                logger.file_prefix = prefix.to_string();
+            }
+        });
+    }
+
+    /// Same as `Log::get_file_name()`, for this logger.  
+    /// このロガーについて、 `Log::get_file_name()` と同じです。  
+    pub fn get_file_name(&self) -> Result<String, Error> {
+        self.read(|logger| logger.file_prefix.to_string())
+    }
+
+    /// Same as `Log::set_file_name_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_file_name_important()` と同じです。  
+    pub fn set_file_name_important(&self, prefix: &str) {
+        self.set_file_name(prefix);
+        self.update(|logger| logger.file_name_important = true);
+    }
+
+    /// Same as `Log::set_file_ext()`, for this logger.  
+    /// このロガーについて、 `Log::set_file_ext()` と同じです。  
+    pub fn set_file_ext(&self, ext: Extension) {
+        self.update_path(|logger| {
+            if !logger.file_ext_important {
+                match ext {
+                    Extension::LogToml => {
+                        logger.file_extension = ".log.toml".to_string();
+                    }
+                    Extension::Log => {
+                        logger.file_extension = ".log".to_string();
+                    }
+                }
+            }
+        });
+    }
+
+    /// Same as `Log::set_file_ext_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_file_ext_important()` と同じです。  
+    pub fn set_file_ext_important(&self, ext: Extension) {
+        self.set_file_ext(ext);
+        self.update(|logger| logger.file_ext_important = true);
+    }
+
+    /// Same as `Log::get_file_ext_str()`, for this logger.  
+    /// このロガーについて、 `Log::get_file_ext_str()` と同じです。  
+    pub fn get_file_ext_str(&self) -> Result<String, Error> {
+        self.read(|logger| logger.file_extension.to_string())
+    }
+
+    /// Same as `Log::set_level()`, for this logger.  
+    /// このロガーについて、 `Log::set_level()` と同じです。  
+    pub fn set_level(&self, level: Level) {
+        self.update(|logger| {
+            if !logger.level_important {
+                logger.level = level;
+            }
+        });
+    }
+
+    /// Same as `Log::set_level_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_level_important()` と同じです。  
+    pub fn set_level_important(&self, level: Level) {
+        self.set_level(level);
+        self.update(|logger| logger.level_important = true);
+    }
+
+    /// Same as `Log::get_level()`, for this logger.  
+    /// このロガーについて、 `Log::get_level()` と同じです。  
+    pub fn get_level(&self) -> Result<Level, Error> {
+        self.read(|logger| logger.level)
+    }
+
+    /// Same as `Log::set_retention_days()`, for this logger.  
+    /// このロガーについて、 `Log::set_retention_days()` と同じです。  
+    pub fn set_retention_days(&self, days: i64) {
+        self.update(|logger| {
+            if !logger.retention_days_important {
+                logger.retention_days = days;
+            }
+        });
+    }
+
+    /// Same as `Log::set_retention_days_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_retention_days_important()` と同じです。  
+    pub fn set_retention_days_important(&self, retention_days: i64) {
+        self.set_retention_days(retention_days);
+        self.update(|logger| logger.retention_days_important = true);
+    }
+
+    /// Same as `Log::get_retention_days()`, for this logger.  
+    /// このロガーについて、 `Log::get_retention_days()` と同じです。  
+    pub fn get_retention_days(&self) -> Result<i64, Error> {
+        self.read(|logger| logger.retention_days)
+    }
+
+    /// Same as `Log::set_retention_files()`, for this logger.  
+    /// このロガーについて、 `Log::set_retention_files()` と同じです。  
+    pub fn set_retention_files(&self, count: usize) {
+        self.update(|logger| {
+            if !logger.retention_files_important {
+                logger.retention_files = count;
+            }
+        });
+    }
+
+    /// Same as `Log::set_retention_files_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_retention_files_important()` と同じです。  
+    pub fn set_retention_files_important(&self, count: usize) {
+        self.set_retention_files(count);
+        self.update(|logger| logger.retention_files_important = true);
+    }
+
+    /// Same as `Log::get_retention_files()`, for this logger.  
+    /// このロガーについて、 `Log::get_retention_files()` と同じです。  
+    pub fn get_retention_files(&self) -> Result<usize, Error> {
+        self.read(|logger| logger.retention_files)
+    }
+
+    /// Same as `Log::set_retention_size()`, for this logger.  
+    /// このロガーについて、 `Log::set_retention_size()` と同じです。  
+    pub fn set_retention_size(&self, bytes: u64) {
+        self.update(|logger| {
+            if !logger.retention_size_important {
+                logger.retention_size = bytes;
+            }
+        });
+    }
+
+    /// Same as `Log::set_retention_size_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_retention_size_important()` と同じです。  
+    pub fn set_retention_size_important(&self, bytes: u64) {
+        self.set_retention_size(bytes);
+        self.update(|logger| logger.retention_size_important = true);
+    }
+
+    /// Same as `Log::get_retention_size()`, for this logger.  
+    /// このロガーについて、 `Log::get_retention_size()` と同じです。  
+    pub fn get_retention_size(&self) -> Result<u64, Error> {
+        self.read(|logger| logger.retention_size)
+    }
+
+    /// Same as `Log::set_timeout_secs()`, for this logger.  
+    /// このロガーについて、 `Log::set_timeout_secs()` と同じです。  
+    pub fn set_timeout_secs(&self, secs: u64) {
+        self.update(|logger| {
+            if !logger.timeout_secs_important {
+                logger.timeout_secs = secs;
+            }
+        });
+    }
+
+    /// Same as `Log::set_timeout_secs_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_timeout_secs_important()` と同じです。  
+    pub fn set_timeout_secs_important(&self, secs: u64) {
+        self.set_timeout_secs(secs);
+        self.update(|logger| logger.timeout_secs_important = true);
+    }
+
+    /// Same as `Log::get_timeout_secs()`, for this logger.  
+    /// このロガーについて、 `Log::get_timeout_secs()` と同じです。  
+    pub fn get_timeout_secs(&self) -> Result<u64, Error> {
+        self.read(|logger| logger.timeout_secs)
+    }
+
+    /// Same as `Log::set_dir()`, for this logger.  
+    /// このロガーについて、 `Log::set_dir()` と同じです。  
+    pub fn set_dir(&self, dir: &str) {
+        if self.read(|logger| logger.dir_important).unwrap_or(true) {
+            return;
+        }
+        // The directory is created without locking the logger.
+        // Nothing is output even if directory creation fails.
+        // It will be retried when the file is opened.
+        // ロガーをロックせずにディレクトリーを作ります。
+        // 作成に失敗しても何も出力しません。ファイルを開くときに再試行します。
+        let _ = fs::create_dir_all(dir);
+        self.update_path(|logger| {
+            if !logger.dir_important {
+                logger.dir = dir.to_string();
+            }
+        });
+    }
+
+    /// Close the file. It will be reopened at the next write.  
+    /// ファイルを閉じます。次の書き込みで開き直します。  
+    fn close_file(&self) {
+        if let Ok(mut file) = self.instance.file.lock() {
+            file.close_file();
+        }
+    }
+
+    /// Same as `Log::set_dir_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_dir_important()` と同じです。  
+    pub fn set_dir_important(&self, dir: &str) {
+        self.set_dir(dir);
+        self.update(|logger| logger.dir_important = true);
+    }
+
+    /// Same as `Log::get_dir()`, for this logger.  
+    /// このロガーについて、 `Log::get_dir()` と同じです。  
+    pub fn get_dir(&self) -> Result<String, Error> {
+        self.read(|logger| logger.dir.to_string())
+    }
+
+    /// Same as `Log::set_max_file_size()`, for this logger.  
+    /// このロガーについて、 `Log::set_max_file_size()` と同じです。  
+    pub fn set_max_file_size(&self, bytes: u64) {
+        self.update(|logger| {
+            if !logger.max_file_size_important {
+                logger.max_file_size = bytes;
+            }
+        });
+    }
+
+    /// Same as `Log::set_max_file_size_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_max_file_size_important()` と同じです。  
+    pub fn set_max_file_size_important(&self, bytes: u64) {
+        self.set_max_file_size(bytes);
+        self.update(|logger| logger.max_file_size_important = true);
+    }
+
+    /// Same as `Log::get_max_file_size()`, for this logger.  
+    /// このロガーについて、 `Log::get_max_file_size()` と同じです。  
+    pub fn get_max_file_size(&self) -> Result<u64, Error> {
+        self.read(|logger| logger.max_file_size)
+    }
+
+    /// Same as `Log::set_rotation()`, for this logger.  
+    /// このロガーについて、 `Log::set_rotation()` と同じです。  
+    pub fn set_rotation(&self, rotation: Rotation) {
+        self.update_path(|logger| {
+            if !logger.rotation_important {
+                logger.rotation = rotation;
+            }
+        });
+    }
+
+    /// Same as `Log::set_rotation_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_rotation_important()` と同じです。  
+    pub fn set_rotation_important(&self, rotation: Rotation) {
+        self.set_rotation(rotation);
+        self.update(|logger| logger.rotation_important = true);
+    }
+
+    /// Same as `Log::get_rotation()`, for this logger.  
+    /// このロガーについて、 `Log::get_rotation()` と同じです。  
+    pub fn get_rotation(&self) -> Result<Rotation, Error> {
+        self.read(|logger| logger.rotation)
+    }
+
+    /// Same as `Log::set_fallback()`, for this logger.  
+    /// このロガーについて、 `Log::set_fallback()` と同じです。  
+    pub fn set_fallback(&self, fallback: Fallback) {
+        self.update(|logger| {
+            if !logger.fallback_important {
+                logger.fallback = fallback;
+            }
+        });
+    }
+
+    /// Same as `Log::set_fallback_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_fallback_important()` と同じです。  
+    pub fn set_fallback_important(&self, fallback: Fallback) {
+        self.set_fallback(fallback);
+        self.update(|logger| logger.fallback_important = true);
+    }
+
+    /// Same as `Log::get_fallback()`, for this logger.  
+    /// このロガーについて、 `Log::get_fallback()` と同じです。  
+    pub fn get_fallback(&self) -> Result<Fallback, Error> {
+        self.read(|logger| logger.fallback)
+    }
+
+    /// Same as `Log::set_queue_capacity()`, for this logger.  
+    /// このロガーについて、 `Log::set_queue_capacity()` と同じです。  
+    pub fn set_queue_capacity(&self, capacity: usize) {
+        self.update(|logger| {
+            if !logger.queue_capacity_important {
+                logger.queue_capacity = capacity;
+                self.instance.writer.configure(logger);
+            }
+        });
+    }
+
+    /// Same as `Log::set_queue_capacity_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_queue_capacity_important()` と同じです。  
+    pub fn set_queue_capacity_important(&self, capacity: usize) {
+        self.set_queue_capacity(capacity);
+        self.update(|logger| logger.queue_capacity_important = true);
+    }
+
+    /// Same as `Log::get_queue_capacity()`, for this logger.  
+    /// このロガーについて、 `Log::get_queue_capacity()` と同じです。  
+    pub fn get_queue_capacity(&self) -> Result<usize, Error> {
+        self.read(|logger| logger.queue_capacity)
+    }
+
+    /// Same as `Log::set_overflow()`, for this logger.  
+    /// このロガーについて、 `Log::set_overflow()` と同じです。  
+    pub fn set_overflow(&self, overflow: Overflow) {
+        self.update(|logger| {
+            if !logger.overflow_important {
+                logger.overflow = overflow;
+                self.instance.writer.configure(logger);
+            }
+        });
+    }
+
+    /// Same as `Log::set_overflow_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_overflow_important()` と同じです。  
+    pub fn set_overflow_important(&self, overflow: Overflow) {
+        self.set_overflow(overflow);
+        self.update(|logger| logger.overflow_important = true);
+    }
+
+    /// Same as `Log::get_overflow()`, for this logger.  
+    /// このロガーについて、 `Log::get_overflow()` と同じです。  
+    pub fn get_overflow(&self) -> Result<Overflow, Error> {
+        self.read(|logger| logger.overflow)
+    }
+
+    /// Same as `Log::set_sync_level()`, for this logger.  
+    /// このロガーについて、 `Log::set_sync_level()` と同じです。  
+    pub fn set_sync_level(&self, level: Option<Level>) {
+        self.update(|logger| {
+            if !logger.sync_level_important {
+                logger.sync_level = level;
+            }
+        });
+    }
+
+    /// Same as `Log::set_sync_level_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_sync_level_important()` と同じです。  
+    pub fn set_sync_level_important(&self, level: Option<Level>) {
+        self.set_sync_level(level);
+        self.update(|logger| logger.sync_level_important = true);
+    }
+
+    /// Same as `Log::get_sync_level()`, for this logger.  
+    /// このロガーについて、 `Log::get_sync_level()` と同じです。  
+    pub fn get_sync_level(&self) -> Result<Option<Level>, Error> {
+        self.read(|logger| logger.sync_level)
+    }
+
+    /// Same as `Log::set_write_mode()`, for this logger.  
+    /// このロガーについて、 `Log::set_write_mode()` と同じです。  
+    pub fn set_write_mode(&self, write_mode: WriteMode) {
+        self.update(|logger| {
+            if !logger.write_mode_important {
+                logger.write_mode = write_mode;
+                self.instance.writer.configure(logger);
+            }
+        });
+    }
+
+    /// Same as `Log::set_write_mode_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_write_mode_important()` と同じです。  
+    pub fn set_write_mode_important(&self, write_mode: WriteMode) {
+        self.set_write_mode(write_mode);
+        self.update(|logger| logger.write_mode_important = true);
+    }
+
+    /// Same as `Log::get_write_mode()`, for this logger.  
+    /// このロガーについて、 `Log::get_write_mode()` と同じです。  
+    pub fn get_write_mode(&self) -> Result<WriteMode, Error> {
+        self.read(|logger| logger.write_mode)
+    }
+
+    /// Same as `Log::set_level_directives()`, for this logger.  
+    /// このロガーについて、 `Log::set_level_directives()` と同じです。  
+    pub fn set_level_directives(&self, directives: LevelDirectives) {
+        self.update(|logger| {
+            if !logger.level_directives_important {
+                logger.level_directives = directives;
+            }
+        });
+    }
+
+    /// Same as `Log::set_level_directives_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_level_directives_important()` と同じです。  
+    pub fn set_level_directives_important(&self, directives: LevelDirectives) {
+        self.set_level_directives(directives);
+        self.update(|logger| logger.level_directives_important = true);
+    }
+
+    /// Same as `Log::get_level_directives()`, for this logger.  
+    /// このロガーについて、 `Log::get_level_directives()` と同じです。  
+    pub fn get_level_directives(&self) -> Result<LevelDirectives, Error> {
+        self.read(|logger| logger.level_directives.clone())
+    }
+
+    /// Same as `Log::dropped_count()`, for this logger.  
+    /// このロガーについて、 `Log::dropped_count()` と同じです。  
+    pub fn dropped_count(&self) -> u64 {
+        self.instance.writer.dropped()
+    }
+
+    /// Same as `Log::set_compress()`, for this logger.  
+    /// このロガーについて、 `Log::set_compress()` と同じです。  
+    pub fn set_compress(&self, compress: bool) {
+        self.update(|logger| {
+            if !logger.compress_important {
+                logger.compress = compress;
+            }
+        });
+    }
+
+    /// Same as `Log::set_compress_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_compress_important()` と同じです。  
+    pub fn set_compress_important(&self, compress: bool) {
+        self.set_compress(compress);
+        self.update(|logger| logger.compress_important = true);
+    }
+
+    /// Same as `Log::get_compress()`, for this logger.  
+    /// このロガーについて、 `Log::get_compress()` と同じです。  
+    pub fn get_compress(&self) -> Result<bool, Error> {
+        self.read(|logger| logger.compress)
+    }
+
+    /// Same as `Log::set_opt()`, for this logger.  
+    /// このロガーについて、 `Log::set_opt()` と同じです。  
+    pub fn set_opt(&self, optimization: Opt) {
+        self.update(|logger| {
+            if !logger.opt_important {
+                logger.opt = optimization;
+            }
+        });
+    }
+
+    /// Same as `Log::set_opt_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_opt_important()` と同じです。  
+    pub fn set_opt_important(&self, optimization: Opt) {
+        self.set_opt(optimization);
+        self.update(|logger| logger.opt_important = true);
+    }
+
+    /// Same as `Log::get_opt()`, for this logger.  
+    /// このロガーについて、 `Log::get_opt()` と同じです。  
+    pub fn get_opt(&self) -> Result<Opt, Error> {
+        self.read(|logger| logger.opt)
+    }
+
+    /// Same as `Log::remove_old_logs()`, for this logger.  
+    /// このロガーについて、 `Log::remove_old_logs()` と同じです。  
+    pub fn remove_old_logs(&self) -> usize {
+        // The files are removed without locking the logger.
+        // ロガーをロックせずにファイルを削除します。
+        let result = self.retention().and_then(|retention| retention.remove());
+        let remove_num = match result {
+            Ok(report) => {
+                for (_, error) in report.failed {
+                    Log::report_error(error);
+                }
+                report.removed.len()
+            }
+            Err(error) => {
+                // The error is not printed, only reported. See `Log::last_error()`.
+                // エラーは表示せず、報告だけします。 `Log::last_error()` を見てください。
+                Log::report_error(error);
+                return 0;
+            }
+        };
+
+        match self.get_opt().unwrap_or(DEFAULT_OPTIMIZATION) {
+            Opt::BeginnersSupport | Opt::Development => {
+                println!(
+                    "casual_logger   | Remove {} log file(s).
+                | If you don't want this message, set `Log::set_opt(Opt::Release);`.",
+                    remove_num
+                );
+            }
+            Opt::Release => {}
+        }
+        remove_num
+    }
+
+    /// Same as `Log::set_auto_remove()`, for this logger.  
+    /// このロガーについて、 `Log::set_auto_remove()` と同じです。  
+    pub fn set_auto_remove(&self, auto_remove: bool) {
+        self.update(|logger| {
+            if !logger.auto_remove_important {
+                logger.auto_remove = auto_remove;
+            }
+        });
+    }
+
+    /// Same as `Log::set_auto_remove_important()`, for this logger.  
+    /// このロガーについて、 `Log::set_auto_remove_important()` と同じです。  
+    pub fn set_auto_remove_important(&self, auto_remove: bool) {
+        self.set_auto_remove(auto_remove);
+        self.update(|logger| logger.auto_remove_important = true);
+    }
+
+    /// Same as `Log::get_auto_remove()`, for this logger.  
+    /// このロガーについて、 `Log::get_auto_remove()` と同じです。  
+    pub fn get_auto_remove(&self) -> Result<bool, Error> {
+        self.read(|logger| logger.auto_remove)
+    }
+
+    /// Same as `Log::remove_old_logs_with_report()`, for this logger.  
+    /// このロガーについて、 `Log::remove_old_logs_with_report()` と同じです。  
+    pub fn remove_old_logs_with_report(&self) -> Result<RemoveReport, Error> {
+        self.retention()?.remove()
+    }
+
+    /// Same as `Log::plan_old_logs()`, for this logger.  
+    /// このロガーについて、 `Log::plan_old_logs()` と同じです。  
+    pub fn plan_old_logs(&self) -> Result<Vec<OldLog>, Error> {
+        self.retention()?.plan()
+    }
+
+    /// Settings to remove old log files. The logger is locked only while they are taken.  
+    /// 古いログ・ファイルを削除するための設定。取る間だけロガーをロックします。  
+    fn retention(&self) -> Result<Retention, Error> {
+        let mut retention = match self.instance.logger.lock() {
+            Ok(logger) => logger.retention(),
+            Err(e) => return Err(Error::from(e)),
+        };
+        // The file being written is kept.
+        // 書き込み中のファイルは残します。
+        match self.instance.file.lock() {
+            Ok(file) => retention.current = file.current_path().map(Path::to_path_buf),
+            Err(e) => return Err(Error::from(e)),
+        }
+        Ok(retention)
+    }
+
+    /// Same as `Log::flush()`, for this logger.  
+    /// このロガーについて、 `Log::flush()` と同じです。  
+    pub fn flush(&self) {
+        self.flush_with(false);
+    }
+
+    /// Same as `Log::sync()`, for this logger.  
+    /// このロガーについて、 `Log::sync()` と同じです。  
+    pub fn sync(&self) {
+        self.flush_with(true);
+    }
+
+    fn flush_with(&self, sync: bool) {
+        let (timeout_secs, opt) = if let Ok(logger) = self.instance.logger.lock() {
+            (logger.timeout_secs, logger.opt)
+        } else {
+            // Error
+            (0, Opt::BeginnersSupport)
+        };
+
+        self.wait_for_logging_to_complete(timeout_secs, sync, |secs, message| {
+            // Do not call 'Log::xxxxx()' in this code block.
+            match opt {
+                Opt::Development => {
+                    // Running background.
+                    println!("casual_logger   | {} sec(s). {}", secs, message,);
+                }
+                Opt::Release | Opt::BeginnersSupport => {}
+            }
+        });
+    }
+    /// Wait for logging to complete.
+    /// The timeout is only a safety net, in case the writer stalls.
+    fn wait_for_logging_to_complete<F>(&self, timeout_secs: u64, sync: bool, count_down: F)
+    where
+        F: Fn(u64, String),
+    {
+        let reply = if let Some(reply) = self.instance.flush(sync) {
+            reply
+        } else {
+            // Already written.
+            return;
+        };
+
+        let mut elapsed_secs = 0;
+        while elapsed_secs < timeout_secs {
+            match reply.recv_timeout(std::time::Duration::from_secs(1)) {
+                Err(RecvTimeoutError::Timeout) => {
+                    elapsed_secs += 1;
+                    count_down(
+                        elapsed_secs,
+                        Log::print_message(Some(self.instance.writer.pending())),
+                    );
+                }
+                // Written, or the writer thread is gone.
+                Ok(_) | Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+
+    /// Same as `Log::enabled()`, for this logger.  
+    /// このロガーについて、 `Log::enabled()` と同じです。  
+    pub fn enabled(&self, level: Level) -> bool {
+        if let Ok(logger) = self.instance.logger.lock() {
+            if logger.enabled(level) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Same as `Log::enabled_for()`, for this logger.  
+    /// このロガーについて、 `Log::enabled_for()` と同じです。  
+    pub fn enabled_for(&self, level: Level, target: &str) -> bool {
+        if let Ok(logger) = self.instance.logger.lock() {
+            if logger.enabled_for(level, target) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Same as `Log::log_t()`, for this logger.  
+    /// このロガーについて、 `Log::log_t()` と同じです。  
+    pub fn log_t(&self, level: Level, target: &str, message: &str, table: &mut Table) {
+        if self.enabled_for(level, target) {
+            self.reserve_table(level, message, false, table);
+        }
+    }
+
+    /// Same as `Log::trace()`, for this logger.  
+    /// このロガーについて、 `Log::trace()` と同じです。  
+    pub fn trace(&self, message: &str) {
+        self.log(Level::Trace, message, false);
+    }
+
+    /// Same as `Log::traceln()`, for this logger.  
+    /// このロガーについて、 `Log::traceln()` と同じです。  
+    pub fn traceln(&self, message: &str) {
+        self.log(Level::Trace, message, true);
+    }
+
+    /// Same as `Log::trace_t()`, for this logger.  
+    /// このロガーについて、 `Log::trace_t()` と同じです。  
+    pub fn trace_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Trace, message, false, table);
+    }
+
+    /// Same as `Log::traceln_t()`, for this logger.  
+    /// このロガーについて、 `Log::traceln_t()` と同じです。  
+    pub fn traceln_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Trace, message, true, table);
+    }
+
+    /// Same as `Log::debug()`, for this logger.  
+    /// このロガーについて、 `Log::debug()` と同じです。  
+    pub fn debug(&self, message: &str) {
+        self.log(Level::Debug, message, false);
+    }
+
+    /// Same as `Log::debugln()`, for this logger.  
+    /// このロガーについて、 `Log::debugln()` と同じです。  
+    pub fn debugln(&self, message: &str) {
+        self.log(Level::Debug, message, true);
+    }
+
+    /// Same as `Log::debug_t()`, for this logger.  
+    /// このロガーについて、 `Log::debug_t()` と同じです。  
+    pub fn debug_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Debug, message, false, table);
+    }
+
+    /// Same as `Log::debugln_t()`, for this logger.  
+    /// このロガーについて、 `Log::debugln_t()` と同じです。  
+    pub fn debugln_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Debug, message, true, table);
+    }
+
+    /// Same as `Log::info()`, for this logger.  
+    /// このロガーについて、 `Log::info()` と同じです。  
+    pub fn info(&self, message: &str) {
+        self.log(Level::Info, message, false);
+    }
+
+    /// Same as `Log::infoln()`, for this logger.  
+    /// このロガーについて、 `Log::infoln()` と同じです。  
+    pub fn infoln(&self, message: &str) {
+        self.log(Level::Info, message, true);
+    }
+
+    /// Same as `Log::info_t()`, for this logger.  
+    /// このロガーについて、 `Log::info_t()` と同じです。  
+    pub fn info_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Info, message, false, table);
+    }
+
+    /// Same as `Log::infoln_t()`, for this logger.  
+    /// このロガーについて、 `Log::infoln_t()` と同じです。  
+    pub fn infoln_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Info, message, true, table);
+    }
+
+    /// Same as `Log::notice()`, for this logger.  
+    /// このロガーについて、 `Log::notice()` と同じです。  
+    pub fn notice(&self, message: &str) {
+        self.log(Level::Notice, message, false);
+    }
+
+    /// Same as `Log::noticeln()`, for this logger.  
+    /// このロガーについて、 `Log::noticeln()` と同じです。  
+    pub fn noticeln(&self, message: &str) {
+        self.log(Level::Notice, message, true);
+    }
+
+    /// Same as `Log::notice_t()`, for this logger.  
+    /// このロガーについて、 `Log::notice_t()` と同じです。  
+    pub fn notice_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Notice, message, false, table);
+    }
+
+    /// Same as `Log::noticeln_t()`, for this logger.  
+    /// このロガーについて、 `Log::noticeln_t()` と同じです。  
+    pub fn noticeln_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Notice, message, true, table);
+    }
+
+    /// Same as `Log::warn()`, for this logger.  
+    /// このロガーについて、 `Log::warn()` と同じです。  
+    pub fn warn(&self, message: &str) {
+        self.log(Level::Warn, message, false);
+    }
+
+    /// Same as `Log::warnln()`, for this logger.  
+    /// このロガーについて、 `Log::warnln()` と同じです。  
+    pub fn warnln(&self, message: &str) {
+        self.log(Level::Warn, message, true);
+    }
+
+    /// Same as `Log::warn_t()`, for this logger.  
+    /// このロガーについて、 `Log::warn_t()` と同じです。  
+    pub fn warn_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Warn, message, false, table);
+    }
+
+    /// Same as `Log::warnln_t()`, for this logger.  
+    /// このロガーについて、 `Log::warnln_t()` と同じです。  
+    pub fn warnln_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Warn, message, true, table);
+    }
+
+    /// Same as `Log::error()`, for this logger.  
+    /// このロガーについて、 `Log::error()` と同じです。  
+    pub fn error(&self, message: &str) {
+        self.log(Level::Error, message, false);
+    }
+
+    /// Same as `Log::errorln()`, for this logger.  
+    /// このロガーについて、 `Log::errorln()` と同じです。  
+    pub fn errorln(&self, message: &str) {
+        self.log(Level::Error, message, true);
+    }
+
+    /// Same as `Log::error_t()`, for this logger.  
+    /// このロガーについて、 `Log::error_t()` と同じです。  
+    pub fn error_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Error, message, false, table);
+    }
+
+    /// Same as `Log::errorln_t()`, for this logger.  
+    /// このロガーについて、 `Log::errorln_t()` と同じです。  
+    pub fn errorln_t(&self, message: &str, table: &mut Table) {
+        self.log_table(Level::Error, message, true, table);
+    }
+
+    /// Same as `Log::fatal()`, for this logger.  
+    /// このロガーについて、 `Log::fatal()` と同じです。  
+    pub fn fatal(&self, message: &str) -> String {
+        self.fatal_table(message, false, &mut Table::default())
+    }
+
+    /// Same as `Log::fatalln()`, for this logger.  
+    /// このロガーについて、 `Log::fatalln()` と同じです。  
+    pub fn fatalln(&self, message: &str) -> String {
+        self.fatal_table(message, true, &mut Table::default())
+    }
+
+    /// Same as `Log::fatal_t()`, for this logger.  
+    /// このロガーについて、 `Log::fatal_t()` と同じです。  
+    pub fn fatal_t(&self, message: &str, table: &mut Table) -> String {
+        self.fatal_table(message, false, table)
+    }
+
+    /// Same as `Log::fatalln_t()`, for this logger.  
+    /// このロガーについて、 `Log::fatalln_t()` と同じです。  
+    pub fn fatalln_t(&self, message: &str, table: &mut Table) -> String {
+        self.fatal_table(message, true, table)
+    }
+
+    /// Write at the level, if it is enabled.  
+    /// 有効なら、そのレベルで書きます。  
+    fn log(&self, level: Level, message: &str, trailing_newline: bool) {
+        if self.enabled(level) {
+            self.reserve_table(level, message, trailing_newline, &mut Table::default());
+        }
+    }
+
+    /// Write the table at the level, if it is enabled.  
+    /// 有効なら、そのレベルでテーブルを書きます。  
+    fn log_table(&self, level: Level, message: &str, trailing_newline: bool, table: &mut Table) {
+        if self.enabled(level) {
+            self.reserve_table(level, message, trailing_newline, table);
+        }
+    }
+
+    /// Fatal runs at any level, and waits for logging to complete or to timeout.  
+    /// Fatal はどのレベルでも実行し、ログの書き込みの完了かタイムアウトを待ちます。  
+    ///
+    /// # Returns
+    ///
+    /// The message, to be used as the first argument of panic!.  
+    /// panic! の最初の引数に使うメッセージ。  
+    fn fatal_table(&self, message: &str, trailing_newline: bool, table: &mut Table) -> String {
+        self.reserve_table(Level::Fatal, message, trailing_newline, table);
+        self.flush();
+        if trailing_newline {
+            format!("{}{}", message, NEW_LINE)
+        } else {
+            message.to_string()
+        }
+    }
+
+    /// Name the table, then write it at any level.  
+    /// テーブルに名前を付けて、どのレベルでも書きます。  
+    pub(crate) fn reserve_table(
+        &self,
+        level: Level,
+        message: &str,
+        trailing_newline: bool,
+        table: &mut Table,
+    ) {
+        table.base_name = Stringifier::create_identify_table_name(self.instance.create_seq());
+        table.level = level;
+        table.message = message.to_string();
+        table.message_trailing_newline = trailing_newline;
+        self.reserve(InternalTable::from_table(table));
+    }
+
+    fn reserve(&self, i_table: InternalTable) {
+        // The settings are not locked, because the writer may hold them during file I/O.
+        match self.instance.writer.write_mode() {
+            WriteMode::Background => self.instance.send(i_table),
+            WriteMode::Synchronous => {
+                if 0 < self.instance.writer.pending() {
+                    // Keep the order with the logs written in the background.
+                    self.flush();
+                }
+                self.instance.write_here(i_table);
+            }
+        }
+    }
+}
+impl Drop for LogHandle {
+    /// Write what is left, then end the writer thread and the watcher thread.  
+    /// 残りを書いてから、書き込みスレッドと見張りスレッドを終わらせます。  
+    fn drop(&mut self) {
+        if config_watcher::is_watcher() {
+            // A watcher thread only borrows the logger while reloading.
+            // 見張りスレッドは、読み直す間だけロガーを借ります。
+            return;
+        }
+        self.instance.watcher.stop();
+        self.flush();
+        self.instance.writer.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "casual_logger-log_handle-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn temp_log(name: &str) -> (LogHandle, PathBuf) {
+        let dir = temp_dir(name);
+        let log = LogHandle::new();
+        log.set_dir(&dir.to_string_lossy());
+        log.set_file_name(name);
+        (log, dir)
+    }
+
+    /// All the logs in the directory.
+    fn read_logs(dir: &Path) -> String {
+        let mut names: Vec<PathBuf> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        names.sort();
+        names
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn optimization_belongs_to_each_logger() {
+        let release = LogHandle::new();
+        release.set_opt_important(Opt::Release);
+        release.set_opt(Opt::Development);
+        let other = LogHandle::new();
+        assert!(matches!(release.get_opt(), Ok(Opt::Release)));
+        assert!(matches!(other.get_opt(), Ok(Opt::BeginnersSupport)));
+    }
+
+    #[test]
+    fn sequential_numbers_belong_to_each_logger() {
+        let a = LogHandle::new();
+        let b = LogHandle::new();
+        assert_eq!(a.instance.create_seq(), 1);
+        assert_eq!(a.instance.create_seq(), 2);
+        assert_eq!(b.instance.create_seq(), 1);
+    }
+
+    #[test]
+    fn flush_returns_after_everything_queued_is_written() {
+        let (log, dir) = temp_log("flush");
+        log.set_queue_capacity(0);
+        for i in 0..500 {
+            log.info(&format!("line {}", i));
+        }
+        log.flush();
+        let text = read_logs(&dir);
+        assert_eq!(text.matches("&Seq=").count(), 500);
+        assert!(text.contains("line 499"));
+
+        // Nothing is queued, so the next flush returns at once.
+        assert!(log.instance.flush(false).is_none());
+        drop(log);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn flush_writes_the_notice_of_dropped_logs() {
+        let (log, dir) = temp_log("flush-dropped");
+        log.set_queue_capacity(1);
+        log.set_overflow(Overflow::DropNewest);
+        for i in 0..500 {
+            log.info(&format!("line {}", i));
+        }
+        log.flush();
+        // Every log is either written or counted in a notice.
+        let text = read_logs(&dir);
+        let dropped = log.instance.writer.dropped();
+        assert_eq!(text.matches("line ").count() as u64 + dropped, 500);
+        assert_eq!(0 < dropped, text.contains("Dropped = "), "{}", text);
+        // The drops have been reported, so the next flush returns at once.
+        assert!(log.instance.flush(false).is_none());
+        drop(log);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// The numbers of the logs written as `<prefix><number>`, in the order of the file.
+    fn numbers(text: &str, prefix: &str) -> Vec<usize> {
+        text.split(prefix)
+            .skip(1)
+            .map(|rest| {
+                let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap();
+                rest[..end].parse().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn each_thread_keeps_the_order_of_its_logs() {
+        let (log, dir) = temp_log("threads");
+        log.set_queue_capacity(4);
+        log.set_overflow(Overflow::Block);
+        let log = std::sync::Arc::new(log);
+        let threads: Vec<_> = (0..4)
+            .map(|t| {
+                let log = std::sync::Arc::clone(&log);
+                std::thread::spawn(move || {
+                    for i in 0..200 {
+                        log.info(&format!("thread{}-{} ", t, i));
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        log.flush();
+
+        // Block drops nothing.
+        let text = read_logs(&dir);
+        assert_eq!(log.instance.writer.dropped(), 0);
+        for t in 0..4 {
+            let numbers = numbers(&text, &format!("thread{}-", t));
+            assert_eq!(numbers, (0..200).collect::<Vec<_>>(), "thread {}", t);
+        }
+        drop(log);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// Send logs while the writer thread cannot write, then let it write them.
+    /// The numbers of the logs kept, in the order of the file.
+    fn overflow_logs(name: &str, overflow: Overflow) -> Vec<usize> {
+        let (log, dir) = temp_log(name);
+        log.set_queue_capacity(2);
+        log.set_overflow(overflow);
+        log.info("start");
+        log.flush();
+        {
+            // The writer thread waits for the file, so the queue fills.
+            let _file = log.instance.file.lock().unwrap();
+            for i in 0..20 {
+                log.info(&format!("queued{} ", i));
+            }
+        }
+        log.flush();
+        let text = read_logs(&dir);
+        let kept = numbers(&text, "queued");
+        // Every log is either written or counted in a notice.
+        let dropped = log.instance.writer.dropped();
+        let noticed: u64 = numbers(&text, "Dropped = ").iter().sum::<usize>() as u64;
+        assert!(0 < dropped);
+        assert_eq!(noticed, dropped, "{}", text);
+        assert_eq!(kept.len() as u64 + dropped, 20, "{}", text);
+        assert!(kept.windows(2).all(|w| w[0] < w[1]), "{}", text);
+        drop(log);
+        let _ = fs::remove_dir_all(&dir);
+        kept
+    }
+
+    #[test]
+    fn drop_newest_keeps_the_first_log() {
+        let kept = overflow_logs("drop-newest", Overflow::DropNewest);
+        assert_eq!(kept.first(), Some(&0), "{:?}", kept);
+    }
+
+    #[test]
+    fn drop_oldest_keeps_the_last_log() {
+        let kept = overflow_logs("drop-oldest", Overflow::DropOldest);
+        assert_eq!(kept.last(), Some(&19), "{:?}", kept);
+    }
+
+    #[test]
+    fn a_new_file_is_opened_when_its_path_changes() {
+        let (log, dir) = temp_log("path");
+        log.set_auto_remove(false);
+        let mut paths = Vec::new();
+        let mut write = |log: &LogHandle| {
+            log.info("path");
+            log.flush();
+            let file = log.instance.file.lock().unwrap();
+            paths.push(file.current_path().unwrap().to_path_buf());
+        };
+        write(&log);
+        log.set_file_name("renamed");
+        write(&log);
+        log.set_file_ext(Extension::Log);
+        write(&log);
+        log.set_rotation(Rotation::Hourly);
+        write(&log);
+        let sub_dir = dir.join("sub");
+        log.set_dir(&sub_dir.to_string_lossy());
+        write(&log);
+        // The same settings keep the file.
+        log.set_rotation(Rotation::Hourly);
+        write(&log);
+
+        assert_eq!(paths[5], paths[4]);
+        paths.dedup();
+        assert_eq!(paths.len(), 5, "{:?}", paths);
+        for path in &paths {
+            assert!(path.exists(), "{:?}", path);
+        }
+        assert!(paths[1].starts_with(&dir), "{:?}", paths);
+        assert!(paths[4].starts_with(&sub_dir), "{:?}", paths);
+        drop(log);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,19 +1,13 @@
-use crate::log_file::LogFile;
+use crate::retention::Retention;
 use crate::{
-    Level, Opt, DEFAULT_LOG_LEVEL, DEFAULT_RETENTION_DAYS, DEFAULT_TIMEOUT_SECS, OPT_STATE, SEQ,
+    Fallback, Level, LevelDirectives, Opt, Overflow, Rotation, WriteMode, DEFAULT_AUTO_REMOVE,
+    DEFAULT_COMPRESS, DEFAULT_DIR, DEFAULT_FALLBACK, DEFAULT_LEVEL_DIRECTIVES, DEFAULT_LOG_LEVEL,
+    DEFAULT_MAX_FILE_SIZE, DEFAULT_OPTIMIZATION, DEFAULT_OVERFLOW, DEFAULT_QUEUE_CAPACITY,
+    DEFAULT_RETENTION_DAYS, DEFAULT_RETENTION_FILES, DEFAULT_RETENTION_SIZE, DEFAULT_SYNC_LEVEL,
+    DEFAULT_TIMEOUT_SECS, DEFAULT_WRITE_MODE,
 };
-use chrono::{Date, Duration, Local, TimeZone};
-use regex::Regex;
-use std::fs;
-use std::fs::{File, OpenOptions};
-use std::ops::Add;
-use std::path::Path;
-use std::sync::Mutex;
-
-lazy_static! {
-    /// Logger grobal variable.
-    pub static ref LOGGER: Mutex<Logger> = Mutex::new(Logger::default());
-}
+use chrono::{DateTime, Local};
+use std::path::{Path, PathBuf};
 
 /// Configuration.  
 ///
@@ -31,7 +25,28 @@ lazy_static! {
 /// |              開始日  
 /// +  
 ///
+/// The StartDate depends on the rotation period.  
+/// 開始日はローテーション周期によって変わります。  
+///
+/// +  
+/// | tic-tac-toe-2020-07-11T15.log.toml          (Rotation::Hourly)  
+/// | tic-tac-toe-2020-07-11.log.toml             (Rotation::Daily, Rotation::Weekly)  
+/// | tic-tac-toe-2020-07-11T15-30-00.log.toml    (Rotation::Interval)  
+/// +  
+///
+/// If the file size limit is exceeded, a part number is inserted:  
+/// ファイル・サイズの上限を超えると、部番号が挿入されます:  
+///
+/// +  
+/// | tic-tac-toe-2020-07-11.1.log.toml  
+/// |                       4-  
+/// |  
+/// |                       4 Part  
+/// |                         部番号  
+/// +  
+///
 /// If you don't like the .toml extension, leave the suffix empty and the .log extension.  
+#[derive(Clone, PartialEq)]
 pub struct Logger {
     /// The file name cannot be changed later.  
     /// ファイル名は後で変更できません。  
@@ -53,14 +68,97 @@ pub struct Logger {
     pub retention_days_important: bool,
     /// File retention days. Delete the file after day from StartDate.
     pub retention_days: i64,
+    /// The file retention count cannot be changed later.  
+    /// ファイル保持数は後で変更できません。  
+    pub retention_files_important: bool,
+    /// Maximum number of files. 0 is unlimited. Delete the oldest first.  
+    /// ファイルの最大数。0 は無制限。古い順に削除します。  
+    pub retention_files: usize,
+    /// The file retention size cannot be changed later.  
+    /// ファイル保持サイズは後で変更できません。  
+    pub retention_size_important: bool,
+    /// Maximum total size of files in bytes. 0 is unlimited. Delete the oldest first.  
+    /// ファイルの最大合計サイズ（バイト）。0 は無制限。古い順に削除します。  
+    pub retention_size: u64,
     /// The timeout seconds cannot be changed later.  
     /// タイムアウト秒は後で変更できません。  
     pub timeout_secs_important: bool,
     /// Timeout seconds.
     pub timeout_secs: u64,
-    /// Controll file.
-    log_file: Option<LogFile>,
+    /// The directory cannot be changed later.  
+    /// ディレクトリーは後で変更できません。  
+    pub dir_important: bool,
+    /// Directory where log files are placed. Default: ".".  
+    /// ログ・ファイルを置くディレクトリー。デフォルト: "."。  
+    pub dir: String,
+    /// The maximum file size cannot be changed later.  
+    /// 最大ファイル・サイズは後で変更できません。  
+    pub max_file_size_important: bool,
+    /// Maximum file size in bytes. 0 is unlimited.  
+    /// 最大ファイル・サイズ（バイト）。0 は無制限。  
+    pub max_file_size: u64,
+    /// The rotation period cannot be changed later.  
+    /// ローテーション周期は後で変更できません。  
+    pub rotation_important: bool,
+    /// Rotation period.  
+    /// ローテーション周期。  
+    pub rotation: Rotation,
+    /// The compression cannot be changed later.  
+    /// 圧縮は後で変更できません。  
+    pub compress_important: bool,
+    /// Compress rotated files.  
+    /// ローテーションされたファイルを圧縮します。  
+    pub compress: bool,
+    /// The automatic removal cannot be changed later.  
+    /// 自動削除は後で変更できません。  
+    pub auto_remove_important: bool,
+    /// Remove old log files each time a file is opened.  
+    /// ファイルを開くたびに古いログ・ファイルを削除します。  
+    pub auto_remove: bool,
+    /// The fallback cannot be changed later.  
+    /// 代替手段は後で変更できません。  
+    pub fallback_important: bool,
+    /// What to do with the log while the file cannot be written.  
+    /// ファイルに書けない間、ログをどうするか。  
+    pub fallback: Fallback,
+    /// The queue capacity cannot be changed later.  
+    /// キューの容量は後で変更できません。  
+    pub queue_capacity_important: bool,
+    /// Maximum number of tables waiting to be written. 0 is unlimited.  
+    /// 書き込みを待つテーブルの最大数。 0 は無制限。  
+    pub queue_capacity: usize,
+    /// The overflow policy cannot be changed later.  
+    /// あふれたときの方針は後で変更できません。  
+    pub overflow_important: bool,
+    /// What to do when the queue is full.  
+    /// キューが満杯のときにどうするか。  
+    pub overflow: Overflow,
+    /// The sync level cannot be changed later.  
+    /// 同期するレベルは後で変更できません。  
+    pub sync_level_important: bool,
+    /// Synchronize the file after writing a log at or above this level.  
+    /// このレベル以上のログを書いた後、ファイルを同期します。  
+    pub sync_level: Option<Level>,
+    /// The write mode cannot be changed later.  
+    /// 書き込み方式は後で変更できません。  
+    pub write_mode_important: bool,
+    /// How to write the log.  
+    /// ログをどう書き込むか。  
+    pub write_mode: WriteMode,
+    /// The level directives cannot be changed later.  
+    /// レベル指定は後で変更できません。  
+    pub level_directives_important: bool,
+    /// Levels per module or target.  
+    /// モジュールまたはターゲットごとのレベル。  
+    pub level_directives: LevelDirectives,
+    /// The optimization cannot be changed later.  
+    /// 最適化は後で変更できません。  
+    pub opt_important: bool,
+    /// Optimization.  
+    /// 最適化。  
+    pub opt: Opt,
 }
+
 impl Default for Logger {
     fn default() -> Self {
         Logger {
@@ -72,31 +170,40 @@ impl Default for Logger {
             level: DEFAULT_LOG_LEVEL,
             retention_days_important: false,
             retention_days: DEFAULT_RETENTION_DAYS,
+            retention_files_important: false,
+            retention_files: DEFAULT_RETENTION_FILES,
+            retention_size_important: false,
+            retention_size: DEFAULT_RETENTION_SIZE,
             timeout_secs_important: false,
             timeout_secs: DEFAULT_TIMEOUT_SECS,
-            log_file: None,
+            dir_important: false,
+            dir: DEFAULT_DIR.to_string(),
+            max_file_size_important: false,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            rotation_important: false,
+            rotation: Rotation::default(),
+            compress_important: false,
+            compress: DEFAULT_COMPRESS,
+            auto_remove_important: false,
+            auto_remove: DEFAULT_AUTO_REMOVE,
+            fallback_important: false,
+            fallback: DEFAULT_FALLBACK,
+            queue_capacity_important: false,
+            queue_capacity: DEFAULT_QUEUE_CAPACITY,
+            overflow_important: false,
+            overflow: DEFAULT_OVERFLOW,
+            sync_level_important: false,
+            sync_level: DEFAULT_SYNC_LEVEL,
+            write_mode_important: false,
+            write_mode: DEFAULT_WRITE_MODE,
+            level_directives_important: false,
+            level_directives: DEFAULT_LEVEL_DIRECTIVES,
+            opt_important: false,
+            opt: DEFAULT_OPTIMIZATION,
         }
     }
 }
 impl Logger {
-    /// Automatic sequential number on thread.
-    pub fn create_seq() -> u128 {
-        SEQ.with(move |seq| {
-            let old = *seq.borrow();
-            *seq.borrow_mut() += 1;
-            old
-        })
-    }
-
-    pub fn get_optimization() -> Opt {
-        if let Ok(opt_state) = OPT_STATE.lock() {
-            opt_state.get()
-        } else {
-            // TODO Error
-            Opt::BeginnersSupport
-        }
-    }
-
     /// Check level.  
     pub fn enabled(&self, level: Level) -> bool {
         if level.number() <= self.level.number() {
@@ -105,137 +212,62 @@ impl Logger {
         false
     }
 
-    /// Create new file, or get exists file.  
-    fn new_today_file(file_prefix: &str, file_extension: &str) -> (Date<Local>, File) {
-        let start_date = Local::today();
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            // Example: 'default-2020-07-11.log.toml'.
-            .open(Path::new(&format!(
-                "{}-{}{}",
-                file_prefix,
-                start_date.format("%Y-%m-%d"),
-                file_extension
-            )))
-            // TODO error handling.
-            .unwrap();
-        (start_date, file)
+    /// Check level for the module or target.  
+    /// モジュールまたはターゲットについてレベルを検査します。  
+    pub fn enabled_for(&self, level: Level, target: &str) -> bool {
+        if self.level_directives.is_empty() {
+            return self.enabled(level);
+        }
+        if self.level.number().max(self.level_directives.max_number()) < level.number() {
+            // More verbose than any directive.
+            // どの指定よりも詳しい。
+            return false;
+        }
+        match self.level_directives.level_for(target) {
+            Some(module_level) => level.number() <= module_level.number(),
+            None => self.enabled(level),
+        }
     }
-    /// For log rotation.
-    pub fn remove_old_logs(&self) -> usize {
-        // Removed files count.
-        let mut count = 0;
-        // Example:
-        //      all = './tic-tac-toe-2020-07-11.log.toml'
-        //      prefix = "tic-tac-toe"
-        //      now = "-2020-07-11"
-        //      extension = ".log.toml" or ".log"
-        let re = if let Ok(x) = Regex::new(&format!(
-            "./{}-{}{}",
-            self.file_prefix, r"(\d{4})-(\d{2})-(\d{2})", self.file_extension
-        )) {
-            x
-        } else {
-            return 0;
-        };
-        // file, directory paths:
-        let paths = if let Ok(x) = fs::read_dir("./") {
-            x
-        } else {
-            return 0;
-        };
-        for path in paths {
-            let name = if let Ok(x) = path {
-                x.path().display().to_string()
-            } else {
-                continue;
-            };
-            // File name pattern match:
-            if let Some(caps) = re.captures(&name) {
-                // Extract year, month, day.
-                let year: i32 = if let Some(cap) = caps.get(1) {
-                    if let Ok(n) = cap.as_str().parse() {
-                        n
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                };
-                let month: u32 = if let Some(cap) = caps.get(2) {
-                    if let Ok(n) = cap.as_str().parse() {
-                        n
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                };
-                let day: u32 = if let Some(cap) = caps.get(3) {
-                    if let Ok(n) = cap.as_str().parse() {
-                        n
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                };
-                if month != 0 && day != 0 {
-                    let file_date = Local.ymd(year, month, day);
 
-                    // Over the retention days.
-                    if self.retention_days < 1 {
-                        if file_date.add(Duration::days(self.retention_days)) < Local::today() {
-                            // Delete dates older than today.
-                            // 今日より古い日付を削除します。
-                            if let Ok(_why) = fs::remove_file(name) {
-                                // Nothing is output even if log writing fails.
-                                // Submitting a message to the competition can result in fouls.
-                                // println!("! {:?}", why.kind());
-                                count += 1;
-                            }
-                        }
-                    } else {
-                        if file_date < Local::today().add(Duration::days(-self.retention_days)) {
-                            // Delete all dates older than today and dates newer than today.
-                            // 今日より古い日付すべてと、今日より新しい日付を削除します。
-                            if let Ok(_why) = fs::remove_file(name) {
-                                // Nothing is output even if log writing fails.
-                                // Submitting a message to the competition can result in fouls.
-                                // println!("! {:?}", why.kind());
-                                count += 1;
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        count
+    /// Example: './default-2020-07-11.log.toml', './default-2020-07-11.1.log.toml'.  
+    pub fn file_path(&self, start: &DateTime<Local>, part: u32) -> PathBuf {
+        Path::new(&self.dir).join(format!(
+            "{}-{}{}{}",
+            self.file_prefix,
+            self.rotation.format_stamp(start),
+            if 0 < part {
+                format!(".{}", part)
+            } else {
+                "".to_string()
+            },
+            self.file_extension
+        ))
     }
 
-    /// Get file, or rotation file.
-    pub fn current_file(&mut self) -> &File {
-        // Check day.
-        let date_changed = if let Some(log_file) = &self.log_file {
-            log_file.start_date < Local::today()
-        } else {
-            false
-        };
-        // Remove file, if day changed.
-        if date_changed {
-            self.log_file = None;
-        }
+    /// Settings that make the path of the log file.  
+    /// If they change, the file is closed and a new one is opened.  
+    /// ログ・ファイルのパスを作る設定。  
+    /// 変わったら、ファイルを閉じて新しいファイルを開きます。  
+    pub fn path_settings(&self) -> (String, String, String, Rotation) {
+        (
+            self.dir.clone(),
+            self.file_prefix.clone(),
+            self.file_extension.clone(),
+            self.rotation,
+        )
+    }
 
-        // New file, if file removed or new.
-        if let None = self.log_file {
-            let (start_date, file) =
-                Logger::new_today_file(&self.file_prefix, &self.file_extension);
-            self.log_file = Some(LogFile::new(start_date, file));
+    /// Settings to remove old log files. Set the file being written to keep it.  
+    /// 古いログ・ファイルを削除するための設定。書き込み中のファイルを設定すると残します。  
+    pub fn retention(&self) -> Retention {
+        Retention {
+            dir: self.dir.clone(),
+            file_prefix: self.file_prefix.clone(),
+            file_extension: self.file_extension.clone(),
+            retention_days: self.retention_days,
+            retention_files: self.retention_files,
+            retention_size: self.retention_size,
+            current: None,
         }
-
-        // Return file handle.
-        // TODO error handling.
-        &self.log_file.as_ref().unwrap().file
     }
 }
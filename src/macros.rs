@@ -0,0 +1,204 @@
+//! Logging macros. The message is formatted only if the level is enabled.  
+//! `trace!()`, `debug!()`, `info!()`, `warn!()` and `error!()` have the same names  
+//! as the macros of the `log` crate. Do not glob import both crates.  
+//! ロギング・マクロ。レベルが有効なときだけメッセージを整形します。  
+//! `trace!()` 、 `debug!()` 、 `info!()` 、 `warn!()` 、 `error!()` は `log` クレートのマクロと  
+//! 同じ名前です。両方のクレートをグロブ・インポートしないでください。  
+
+/// Used by the logging macros.  
+/// The format arguments and the keys are split at the first `;`.  
+/// ロギング・マクロが使います。  
+/// 書式引数とキーは、最初の `;` で分けます。  
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __casual_logger_t {
+    (@split $level:ident [$($arg:tt)*] ; $($rest:tt)*) => {
+        $crate::__casual_logger_t!(@log $level [$($arg)*] $($rest)*)
+    };
+    (@split $level:ident [$($arg:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::__casual_logger_t!(@split $level [$($arg)* $next] $($rest)*)
+    };
+    (@split $level:ident [$($arg:tt)*]) => {
+        $crate::__casual_logger_t!(@log $level [$($arg)*])
+    };
+    (@log Fatal [$($arg:tt)+] $($key:ident = $value:expr),* $(,)?) => {{
+        let mut table = $crate::Table::default();
+        table
+            .str("File", file!())
+            .uint("Line", line!() as u128)
+            .str("Module", module_path!());
+        $($crate::TableValue::insert_into(&$value, &mut table, stringify!($key));)*
+        $crate::Log::fatal_t(&format!($($arg)+), &mut table)
+    }};
+    (@log $level:ident [$($arg:tt)+] $($key:ident = $value:expr),* $(,)?) => {
+        if $crate::Log::enabled_for($crate::Level::$level, module_path!()) {
+            let mut table = $crate::Table::default();
+            table
+                .str("File", file!())
+                .uint("Line", line!() as u128)
+                .str("Module", module_path!());
+            $($crate::TableValue::insert_into(&$value, &mut table, stringify!($key));)*
+            $crate::Log::log_t(
+                $crate::Level::$level,
+                module_path!(),
+                &format!($($arg)+),
+                &mut table,
+            );
+        }
+    };
+}
+
+/// Trace level. Same as `trace_t!()` without keys.  
+/// The `log` crate has a macro of the same name.  
+/// If both are used, import by name, or write `casual_logger::trace!()`.  
+/// Trace レベル。キー無しの `trace_t!()` と同じです。  
+/// `log` クレートに同じ名前のマクロがあります。  
+/// 両方を使うなら、名前でインポートするか、 `casual_logger::trace!()` と書いてください。  
+///
+/// ```no_run
+/// use casual_logger::trace;
+///
+/// let x = 1;
+/// trace!("x={}", x);
+/// ```
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@log Trace [$($arg)+])
+    };
+}
+
+/// Trace level, with keys. "File", "Line" and "Module" are added.  
+/// If the level is not enabled for the module, nothing is formatted.  
+/// See also: `Log::set_level_directives()`.  
+/// キー付きの Trace レベル。 "File" 、 "Line" 、 "Module" を追加します。  
+/// そのモジュールについてレベルが有効でなければ、何も整形しません。  
+///
+/// ```no_run
+/// use casual_logger::trace_t;
+///
+/// let weight = 97.0;
+/// trace_t!("Weighed {} times.", 3; weight = weight, unit = "kg");
+/// ```
+#[macro_export]
+macro_rules! trace_t {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@split Trace [] $($arg)+)
+    };
+}
+
+/// Debug level. See also: `trace!()`.  
+/// Debug レベル。  
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@log Debug [$($arg)+])
+    };
+}
+
+/// Debug level, with keys. See also: `trace_t!()`.  
+/// キー付きの Debug レベル。  
+#[macro_export]
+macro_rules! debug_t {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@split Debug [] $($arg)+)
+    };
+}
+
+/// Info level. See also: `trace!()`.  
+/// Info レベル。  
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@log Info [$($arg)+])
+    };
+}
+
+/// Info level, with keys. See also: `trace_t!()`.  
+/// キー付きの Info レベル。  
+#[macro_export]
+macro_rules! info_t {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@split Info [] $($arg)+)
+    };
+}
+
+/// Notice level. See also: `trace!()`.  
+/// Notice レベル。  
+#[macro_export]
+macro_rules! notice {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@log Notice [$($arg)+])
+    };
+}
+
+/// Notice level, with keys. See also: `trace_t!()`.  
+/// キー付きの Notice レベル。  
+#[macro_export]
+macro_rules! notice_t {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@split Notice [] $($arg)+)
+    };
+}
+
+/// Warn level. See also: `trace!()`.  
+/// Warn レベル。  
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@log Warn [$($arg)+])
+    };
+}
+
+/// Warn level, with keys. See also: `trace_t!()`.  
+/// キー付きの Warn レベル。  
+#[macro_export]
+macro_rules! warn_t {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@split Warn [] $($arg)+)
+    };
+}
+
+/// Error level. See also: `trace!()`.  
+/// Error レベル。  
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@log Error [$($arg)+])
+    };
+}
+
+/// Error level, with keys. See also: `trace_t!()`.  
+/// キー付きの Error レベル。  
+#[macro_export]
+macro_rules! error_t {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@split Error [] $($arg)+)
+    };
+}
+
+/// Fatal level. Returns the message for `panic!()`. See also: `fatal_t!()`.  
+/// Fatal レベル。 `panic!()` のためにメッセージを返します。  
+#[macro_export]
+macro_rules! fatal {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@log Fatal [$($arg)+])
+    };
+}
+
+/// Fatal level, with keys. Returns the message for `panic!()`.  
+/// The message is always formatted.  
+/// キー付きの Fatal レベル。 `panic!()` のためにメッセージを返します。  
+/// メッセージは常に整形します。  
+///
+/// ```no_run
+/// use casual_logger::fatal_t;
+///
+/// panic!(fatal_t!("Out of memory."; bytes = 1024));
+/// ```
+#[macro_export]
+macro_rules! fatal_t {
+    ($($arg:tt)+) => {
+        $crate::__casual_logger_t!(@split Fatal [] $($arg)+)
+    };
+}
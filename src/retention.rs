@@ -0,0 +1,373 @@
+//! Remove old log files.  
+//! 古いログ・ファイルを削除します。  
+use crate::compressor::COMPRESSED_EXTENSION;
+use crate::{Error, Log, OldLog, RemoveReason, RemoveReport};
+use chrono::{Duration, Local, NaiveDate};
+use regex::{Captures, Regex};
+use std::fs;
+use std::io;
+use std::ops::Add;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+
+/// Settings to remove old log files, taken from the logger.  
+/// It is used without locking the logger.  
+/// 古いログ・ファイルを削除するための、ロガーから取った設定。  
+/// ロガーをロックせずに使います。  
+#[derive(Clone, Debug)]
+pub struct Retention {
+    pub dir: String,
+    pub file_prefix: String,
+    pub file_extension: String,
+    pub retention_days: i64,
+    pub retention_files: usize,
+    pub retention_size: u64,
+    /// The file being written. It is never deleted.  
+    /// 書き込み中のファイル。決して削除しません。  
+    pub current: Option<PathBuf>,
+}
+impl Retention {
+    /// Remove old log files in a separate thread.  
+    /// Nothing is displayed. Errors are reported to `Log::last_error()`.  
+    /// 別スレッドで古いログ・ファイルを削除します。  
+    /// 何も表示しません。エラーは `Log::last_error()` に報告します。  
+    pub fn remove_in_background(self) {
+        thread::spawn(move || match self.remove() {
+            Ok(report) => {
+                for (_, error) in report.failed {
+                    Log::report_error(error);
+                }
+            }
+            Err(error) => Log::report_error(error),
+        });
+    }
+
+    /// For log rotation.  
+    ///
+    /// # Returns
+    ///
+    /// Deleted log files and errors, or error.  
+    /// 削除したログ・ファイルとエラー、またはエラー。  
+    pub fn remove(&self) -> Result<RemoveReport, Error> {
+        let mut report = RemoveReport::default();
+        for old_log in self.plan()? {
+            match fs::remove_file(&old_log.path) {
+                Ok(_) => report.removed.push(old_log),
+                // Already removed by another sweep.
+                // 別の削除で既に消えています。
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                // Keep going with the rest. The failure is in the report.
+                // 残りを続けます。失敗はレポートに入ります。
+                Err(e) => {
+                    let error = Error::remove(&old_log.path, &e);
+                    report.failed.push((old_log, error));
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// List the log files to be deleted. Nothing is deleted.  
+    /// 削除されるログ・ファイルを一覧します。何も削除しません。  
+    ///
+    /// # Returns
+    ///
+    /// Log files to be deleted, or error.  
+    /// 削除されるログ・ファイル、またはエラー。  
+    pub fn plan(&self) -> Result<Vec<OldLog>, Error> {
+        let mut plan = Vec::new();
+        // Example:
+        //      all = 'tic-tac-toe-2020-07-11.log.toml'
+        //      prefix = "tic-tac-toe"
+        //      now = "-2020-07-11", "-2020-07-11T15" or "-2020-07-11T15-30-00"
+        //      part = "" or ".1"
+        //      extension = ".log.toml" or ".log"
+        //      compressed = "" or ".gz"
+        //
+        // Files of any rotation period are matched, so that files
+        // from before the rotation period was changed are also removed.
+        // 周期を変更する前のファイルも削除するよう、どの周期のファイルにも一致させます。
+        //
+        // The prefix and extension are escaped, and the whole file name must match,
+        // so that other applications' files are not removed.
+        // 他のアプリケーションのファイルを削除しないよう、接頭辞と拡張子はエスケープし、
+        // ファイル名全体が一致しなければいけません。
+        let re = match Regex::new(&format!(
+            "^{}-{}{}(?:{})?$",
+            regex::escape(&self.file_prefix),
+            r"(\d{4})-(\d{2})-(\d{2})(?:T(\d{2})(?:-(\d{2})-(\d{2}))?)?(?:\.(\d+))?",
+            regex::escape(&self.file_extension),
+            regex::escape(COMPRESSED_EXTENSION)
+        )) {
+            Ok(x) => x,
+            Err(e) => return Err(Error::Pattern(e.to_string())),
+        };
+        // file, directory paths:
+        let paths = match fs::read_dir(&self.dir) {
+            Ok(x) => x,
+            // There are no log files yet.
+            // まだログ・ファイルはありません。
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(plan),
+            Err(e) => return Err(Error::read_dir(Path::new(&self.dir), &e)),
+        };
+        let current_name = self
+            .current
+            .as_ref()
+            .and_then(|current| current.file_name())
+            .map(|name| name.to_os_string());
+        // Files that survived the retention days.
+        // 保持日数を生き延びたファイル。
+        let mut survivors = Vec::new();
+        let today = Local::now().date_naive();
+        for path in paths {
+            let (name, file_name, is_current) = if let Ok(x) = path {
+                let is_current = Some(x.file_name()) == current_name;
+                (
+                    x.path(),
+                    x.file_name().to_string_lossy().to_string(),
+                    is_current,
+                )
+            } else {
+                continue;
+            };
+            // File name pattern match:
+            let caps = if let Some(caps) = re.captures(&file_name) {
+                caps
+            } else {
+                continue;
+            };
+            // Extract year, month, day.
+            // The retention is judged by date, even if the rotation period is finer.
+            // ローテーション周期がもっと細かくても、保持は日付で判定します。
+            let file_date = if let Some(file_date) = NaiveDate::from_ymd_opt(
+                Retention::capture_number(&caps, 1),
+                Retention::capture_number(&caps, 2),
+                Retention::capture_number(&caps, 3),
+            ) {
+                file_date
+            } else {
+                continue;
+            };
+
+            // Over the retention days.
+            // The file being written is not expired,
+            // even if a weekly period started before the retention days.
+            // 週ごとの周期が保持日数より前に始まっていても、書き込み中のファイルは期限切れにしません。
+            let expired = if is_current {
+                false
+            } else if self.retention_days < 1 {
+                // Delete dates older than today.
+                // 今日より古い日付を削除します。
+                file_date.add(Duration::days(self.retention_days)) < today
+            } else {
+                // Delete all dates older than today and dates newer than today.
+                // 今日より古い日付すべてと、今日より新しい日付を削除します。
+                file_date < today.add(Duration::days(-self.retention_days))
+            };
+            if expired {
+                plan.push(OldLog::new(name, RemoveReason::RetentionDays));
+                continue;
+            }
+
+            // Extract hour, minute, second and part for ordering.
+            // 並べ替えのために、時、分、秒、部番号を取り出します。
+            let file_time = file_date.and_hms_opt(
+                Retention::capture_number(&caps, 4),
+                Retention::capture_number(&caps, 5),
+                Retention::capture_number(&caps, 6),
+            );
+            let part: u32 = Retention::capture_number(&caps, 7);
+            let size = if let Ok(metadata) = fs::metadata(&name) {
+                metadata.len()
+            } else {
+                0
+            };
+            survivors.push((is_current, file_time, part, size, name));
+        }
+
+        // Oldest first. The file being written comes last.
+        // 古い順。書き込み中のファイルは最後です。
+        survivors.sort();
+        let mut total_size: u64 = survivors.iter().map(|(_, _, _, size, _)| size).sum();
+        let mut rest = survivors.len();
+        for (_, _, _, size, name) in survivors {
+            // The last file, the one being written or else the newest, is always kept.
+            // 最後のファイル、つまり書き込み中のファイルか一番新しいファイルは常に残します。
+            if rest < 2 {
+                break;
+            }
+            let reason = if 0 < self.retention_files && self.retention_files < rest {
+                RemoveReason::RetentionFiles
+            } else if 0 < self.retention_size && self.retention_size < total_size {
+                RemoveReason::RetentionSize
+            } else {
+                break;
+            };
+            plan.push(OldLog::new(name, reason));
+            // Even if it cannot be deleted, it is not counted
+            // so that newer files are not deleted instead.
+            // 削除できなくても、代わりに新しいファイルを削除しないように数えません。
+            rest -= 1;
+            total_size -= size;
+        }
+        Ok(plan)
+    }
+
+    /// Number in the file name. 0 if not exists.  
+    /// ファイル名の中の数。無ければ 0。  
+    fn capture_number<T: FromStr + Default>(caps: &Captures, i: usize) -> T {
+        match caps.get(i) {
+            Some(cap) => cap.as_str().parse().unwrap_or_default(),
+            None => T::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RemoveReason;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "casual_logger-retention-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn stamp(days_ago: i64) -> String {
+        (Local::now().date_naive() - Duration::days(days_ago))
+            .format("%Y-%m-%d")
+            .to_string()
+    }
+
+    fn create(dir: &Path, name: &str, size: usize) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, vec![b'a'; size]).unwrap();
+        path
+    }
+
+    fn retention(dir: &Path) -> Retention {
+        Retention {
+            dir: dir.to_string_lossy().to_string(),
+            file_prefix: "app".to_string(),
+            file_extension: ".log.toml".to_string(),
+            retention_days: 30,
+            retention_files: 0,
+            retention_size: 0,
+            current: None,
+        }
+    }
+
+    fn names(plan: &[OldLog]) -> Vec<(String, RemoveReason)> {
+        plan.iter()
+            .map(|old_log| {
+                (
+                    old_log
+                        .path
+                        .file_name()
+                        .unwrap()
+                        .to_string_lossy()
+                        .to_string(),
+                    old_log.reason,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn plan_removes_the_oldest_first() {
+        let dir = temp_dir("order");
+        let old = format!("app-{}.log.toml", stamp(2));
+        let old_part = format!("app-{}.1.log.toml.gz", stamp(2));
+        let hourly = format!("app-{}T09.log.toml", stamp(1));
+        let newest = format!("app-{}.log.toml", stamp(0));
+        for name in &[&newest, &hourly, &old_part, &old] {
+            create(&dir, name, 10);
+        }
+        // Not a log file of this application.
+        create(&dir, &format!("app-other-{}.log.toml", stamp(5)), 10);
+        create(&dir, &format!("app-{}.log", stamp(5)), 10);
+
+        let mut retention = retention(&dir);
+        retention.retention_files = 2;
+        assert_eq!(
+            names(&retention.plan().unwrap()),
+            vec![
+                (old, RemoveReason::RetentionFiles),
+                (old_part, RemoveReason::RetentionFiles),
+            ]
+        );
+
+        retention.retention_files = 0;
+        retention.retention_size = 25;
+        assert_eq!(names(&retention.plan().unwrap()).len(), 2);
+        // The newest file is kept even if it is over the size.
+        retention.retention_size = 1;
+        assert_eq!(
+            names(&retention.plan().unwrap())
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<String>>(),
+            vec![
+                format!("app-{}.log.toml", stamp(2)),
+                format!("app-{}.1.log.toml.gz", stamp(2)),
+                hourly,
+            ]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn plan_removes_expired_files() {
+        let dir = temp_dir("expired");
+        let expired = format!("app-{}.log.toml", stamp(3));
+        create(&dir, &expired, 10);
+        create(&dir, &format!("app-{}.log.toml", stamp(0)), 10);
+        let mut retention = retention(&dir);
+        retention.retention_days = 2;
+        assert_eq!(
+            names(&retention.plan().unwrap()),
+            vec![(expired, RemoveReason::RetentionDays)]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn plan_keeps_the_current_file() {
+        let dir = temp_dir("current");
+        // A weekly file started 5 days ago, with 1 retention day.
+        let current = create(&dir, &format!("app-{}.log.toml", stamp(5)), 10);
+        let expired = format!("app-{}.1.log.toml", stamp(5));
+        create(&dir, &expired, 10);
+        let mut retention = retention(&dir);
+        retention.retention_days = 1;
+        retention.current = Some(current.clone());
+        assert_eq!(
+            names(&retention.plan().unwrap()),
+            vec![(expired, RemoveReason::RetentionDays)]
+        );
+
+        // The current file is kept even if it is older than a newer file.
+        let newer = format!("app-{}.log.toml", stamp(0));
+        create(&dir, &newer, 10);
+        retention.retention_days = 30;
+        retention.retention_files = 1;
+        assert_eq!(
+            names(&retention.plan().unwrap())
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<String>>(),
+            vec![format!("app-{}.1.log.toml", stamp(5)), newer]
+        );
+        let report = retention.remove().unwrap();
+        assert_eq!(report.removed.len(), 2);
+        assert!(current.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,230 @@
+//! Rotation period of the log file.  
+//! ログ・ファイルのローテーション周期です。  
+use crate::Rotation;
+use chrono::{DateTime, Datelike, Duration, Local, LocalResult, NaiveDateTime, TimeZone, Timelike};
+
+impl Rotation {
+    /// Start of the period that contains the time.  
+    /// その時刻を含む周期の開始時刻。  
+    ///
+    /// `Rotation::Interval` is counted from 1970-01-01 00:00:00 local time.  
+    /// If the interval is less than 1 second, it is the same as `Rotation::Daily`.  
+    /// `Rotation::Interval` はローカル時刻の 1970-01-01 00:00:00 から数えます。  
+    /// 間隔が1秒未満なら、 `Rotation::Daily` と同じです。  
+    pub fn start_of(&self, time: DateTime<Local>) -> DateTime<Local> {
+        self.start_in(time)
+    }
+
+    /// Same as `start_of`, in any time zone.  
+    /// `start_of` と同じですが、任意のタイムゾーンで計算します。  
+    fn start_in<Tz: TimeZone>(&self, time: DateTime<Tz>) -> DateTime<Tz> {
+        let naive = time.naive_local();
+        let start = match self {
+            Rotation::Hourly => naive.date().and_hms_opt(naive.hour(), 0, 0),
+            Rotation::Daily => naive.date().and_hms_opt(0, 0, 0),
+            Rotation::Weekly => (naive.date()
+                - Duration::days(naive.weekday().num_days_from_monday().into()))
+            .and_hms_opt(0, 0, 0),
+            Rotation::Interval(interval) => {
+                let secs = interval.num_seconds();
+                if secs < 1 {
+                    naive.date().and_hms_opt(0, 0, 0)
+                } else {
+                    let local_secs = naive.and_utc().timestamp();
+                    DateTime::from_timestamp(local_secs - local_secs.rem_euclid(secs), 0)
+                        .map(|x| x.naive_utc())
+                }
+            }
+        };
+        match start.and_then(|x| Self::resolve_local(&time.timezone(), x)) {
+            Some(start) => start,
+            None => time,
+        }
+    }
+
+    /// Local time to date time. If the local time is skipped by daylight saving time,
+    /// it is the next valid local time, so the start of a period is always the same.  
+    /// ローカル時刻を日時にします。夏時間で飛ばされた時刻なら、次に存在する時刻にするので、
+    /// 周期の開始時刻は常に同じです。  
+    fn resolve_local<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> Option<DateTime<Tz>> {
+        // A gap is shorter than a day.
+        // 飛ばされる時間は1日より短いです。
+        let mut local = local;
+        for _ in 0..=24 * 60 {
+            match tz.from_local_datetime(&local) {
+                LocalResult::Single(x) => return Some(x),
+                LocalResult::Ambiguous(earliest, _) => return Some(earliest),
+                LocalResult::None => {}
+            }
+            local = local.with_second(0)? + Duration::minutes(1);
+        }
+        None
+    }
+
+    /// Stamp in the file name.  
+    /// ファイル名に入る日時です。  
+    ///
+    /// * `Rotation::Hourly` - '2020-07-11T15'.
+    /// * `Rotation::Daily`, `Rotation::Weekly` - '2020-07-11'.
+    /// * `Rotation::Interval` - '2020-07-11T15-30-00'. Less than 1 second is the same as `Rotation::Daily`.
+    pub fn format_stamp(&self, start: &DateTime<Local>) -> String {
+        match self {
+            Rotation::Hourly => start.format("%Y-%m-%dT%H"),
+            Rotation::Daily | Rotation::Weekly => start.format("%Y-%m-%d"),
+            Rotation::Interval(interval) if interval.num_seconds() < 1 => start.format("%Y-%m-%d"),
+            Rotation::Interval(_) => start.format("%Y-%m-%dT%H-%M-%S"),
+        }
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Rotation;
+    use chrono::{
+        DateTime, Duration, FixedOffset, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc,
+    };
+
+    /// Clocks go forward from 00:00 to 01:00 on 2021-03-14.
+    #[derive(Clone, Debug)]
+    struct MidnightGap;
+    impl MidnightGap {
+        fn gap() -> (NaiveDateTime, NaiveDateTime) {
+            let date = NaiveDate::from_ymd_opt(2021, 3, 14).unwrap();
+            (
+                date.and_hms_opt(0, 0, 0).unwrap(),
+                date.and_hms_opt(1, 0, 0).unwrap(),
+            )
+        }
+        fn offset(summer: bool) -> FixedOffset {
+            FixedOffset::east_opt(if summer { 3600 } else { 0 }).unwrap()
+        }
+    }
+    impl TimeZone for MidnightGap {
+        type Offset = FixedOffset;
+        fn from_offset(_offset: &FixedOffset) -> Self {
+            MidnightGap
+        }
+        fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+            let (begin, end) = Self::gap();
+            if *local < begin {
+                LocalResult::Single(Self::offset(false))
+            } else if *local < end {
+                LocalResult::None
+            } else {
+                LocalResult::Single(Self::offset(true))
+            }
+        }
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Self::offset(*utc >= Self::gap().0)
+        }
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, s).unwrap()
+    }
+
+    #[test]
+    fn hourly_boundaries() {
+        let rotation = Rotation::Hourly;
+        assert_eq!(
+            rotation.start_in(utc(2020, 7, 11, 15, 59, 59)),
+            utc(2020, 7, 11, 15, 0, 0)
+        );
+        assert_eq!(
+            rotation.start_in(utc(2020, 7, 11, 16, 0, 0)),
+            utc(2020, 7, 11, 16, 0, 0)
+        );
+    }
+
+    #[test]
+    fn daily_boundaries() {
+        let rotation = Rotation::default();
+        assert_eq!(
+            rotation.start_in(utc(2020, 7, 11, 23, 59, 59)),
+            utc(2020, 7, 11, 0, 0, 0)
+        );
+        assert_eq!(
+            rotation.start_in(utc(2020, 7, 12, 0, 0, 0)),
+            utc(2020, 7, 12, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn weekly_starts_on_monday() {
+        let rotation = Rotation::Weekly;
+        // 2020-07-11 is a Saturday, 2020-07-13 is a Monday.
+        assert_eq!(
+            rotation.start_in(utc(2020, 7, 11, 12, 0, 0)),
+            utc(2020, 7, 6, 0, 0, 0)
+        );
+        assert_eq!(
+            rotation.start_in(utc(2020, 7, 13, 0, 0, 0)),
+            utc(2020, 7, 13, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn interval_boundaries() {
+        let rotation = Rotation::Interval(Duration::minutes(30));
+        assert_eq!(
+            rotation.start_in(utc(2020, 7, 11, 15, 29, 59)),
+            utc(2020, 7, 11, 15, 0, 0)
+        );
+        assert_eq!(
+            rotation.start_in(utc(2020, 7, 11, 15, 30, 0)),
+            utc(2020, 7, 11, 15, 30, 0)
+        );
+        // Less than 1 second is daily.
+        assert_eq!(
+            Rotation::Interval(Duration::zero()).start_in(utc(2020, 7, 11, 15, 30, 0)),
+            utc(2020, 7, 11, 0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn start_in_daylight_saving_gap_is_stable() {
+        let tz = MidnightGap;
+        let rotation = Rotation::Daily;
+        // Midnight does not exist, so the day starts at 01:00.
+        let expected = tz.with_ymd_and_hms(2021, 3, 14, 1, 0, 0).unwrap();
+        let first = rotation.start_in(tz.with_ymd_and_hms(2021, 3, 14, 1, 0, 1).unwrap());
+        let second = rotation.start_in(tz.with_ymd_and_hms(2021, 3, 14, 18, 30, 0).unwrap());
+        assert_eq!(first, expected);
+        assert_eq!(second, expected);
+        // The day before is not affected.
+        assert_eq!(
+            rotation.start_in(tz.with_ymd_and_hms(2021, 3, 13, 23, 59, 59).unwrap()),
+            tz.with_ymd_and_hms(2021, 3, 13, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            Rotation::Hourly.start_in(tz.with_ymd_and_hms(2021, 3, 14, 1, 45, 0).unwrap()),
+            expected
+        );
+    }
+
+    #[test]
+    fn format_stamp() {
+        let start = chrono::Local
+            .with_ymd_and_hms(2020, 7, 11, 15, 30, 0)
+            .unwrap();
+        assert_eq!(Rotation::Hourly.format_stamp(&start), "2020-07-11T15");
+        assert_eq!(Rotation::Daily.format_stamp(&start), "2020-07-11");
+        assert_eq!(Rotation::Weekly.format_stamp(&start), "2020-07-11");
+        assert_eq!(
+            Rotation::Interval(Duration::minutes(30)).format_stamp(&start),
+            "2020-07-11T15-30-00"
+        );
+        // Less than 1 second is daily.
+        assert_eq!(
+            Rotation::Interval(Duration::milliseconds(500)).format_stamp(&start),
+            "2020-07-11"
+        );
+    }
+}
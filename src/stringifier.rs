@@ -16,11 +16,11 @@ lazy_static! {
 // For multi-platform. Windows, or not.
 // マルチプラットフォーム対応。Windowsか、それ以外か。
 #[cfg(windows)]
-const NEW_LINE_SEQUENCE: &'static str = "\\r\\n";
+const NEW_LINE_SEQUENCE: &str = "\\r\\n";
 #[cfg(not(windows))]
-const NEW_LINE_SEQUENCE: &'static str = "\\n";
-const CARRIAGE_RETURN: &'static char = &'\r';
-const LINE_FEED: &'static char = &'\n';
+const NEW_LINE_SEQUENCE: &str = "\\n";
+const CARRIAGE_RETURN: &char = &'\r';
+const LINE_FEED: &char = &'\n';
 enum NewLineType {
     /// `\r\n`.
     CarriageReturnLineFeed,
@@ -44,7 +44,7 @@ impl Stringifier {
             Local::now().format("%Y-%m-%dT%H:%M:%S%z"),
             // Process ID.
             process::id(),
-            Stringifier::thread_id().to_string(),
+            Stringifier::thread_id(),
             seq
         )
         .to_string()
@@ -130,10 +130,10 @@ impl Stringifier {
     ///
     /// Note: Even on Windows, there may be mixed '\n' line breaks.  
     /// 注意: Windows でも、'\n' 改行が混じっていることがあります。  
-    fn which_new_line_type(ch_vec: &Vec<char>) -> Option<NewLineType> {
+    fn which_new_line_type(ch_vec: &[char]) -> Option<NewLineType> {
         // If the last '\n'.
         // 最後が '\n' なら。
-        if 0 < ch_vec.len() && &ch_vec[ch_vec.len() - 1] == LINE_FEED {
+        if !ch_vec.is_empty() && &ch_vec[ch_vec.len() - 1] == LINE_FEED {
             // if the second from the last is '\r'.
             // 最後から二番目が '\r' なら。
             if 1 < ch_vec.len() && &ch_vec[ch_vec.len() - 2] == CARRIAGE_RETURN {
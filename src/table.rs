@@ -42,6 +42,14 @@ impl InternalTable {
             table: KindOfTable::ArrayOfTable(aot.clone()),
         }
     }
+    /// Log level. None if it is not a table.  
+    /// ログ・レベル。テーブルでなければ None 。  
+    pub fn level(&self) -> Option<Level> {
+        match &self.table {
+            KindOfTable::Table(table) => Some(table.level),
+            KindOfTable::ArrayOfTable(_) => None,
+        }
+    }
     /// Example: `Info = "Message"`.
     pub fn create_log_level_kv_pair(table: &Table) -> String {
         let message = if table.message_trailing_newline {
@@ -67,17 +75,14 @@ impl InternalTable {
             indent_spaces,
             None,
             match &self.table {
-                KindOfTable::Table(table) => Some(InternalTable::create_log_level_kv_pair(&table)),
+                KindOfTable::Table(table) => Some(InternalTable::create_log_level_kv_pair(table)),
                 KindOfTable::ArrayOfTable(_) => None,
             },
-            &self,
+            self,
         );
         // End of recursive.
         // New line.
-        toml.push_str(
-            "
-",
-        );
+        toml.push('\n');
         toml.to_string()
     }
 
@@ -101,7 +106,7 @@ impl InternalTable {
         // Table or Array of table.
         match &i_table.table {
             KindOfTable::Table(k_table) => {
-                toml.push_str(&indent_spaces);
+                toml.push_str(indent_spaces);
                 toml.push_str(&format!(
                     "[{}]
 ",
@@ -114,7 +119,7 @@ impl InternalTable {
                 // Sorted map.
                 if let Some(sorted_map) = &k_table.sorted_map {
                     for (k2, formatted_v) in sorted_map {
-                        toml.push_str(&indent_spaces);
+                        toml.push_str(indent_spaces);
                         toml.push_str(&format!(
                             "{} = {}
 ",
@@ -125,7 +130,7 @@ impl InternalTable {
                 // Sub tables.
                 if let Some(sub_tables) = &k_table.sub_tables {
                     indent_spaces.push_str("  ");
-                    for (_k1, sub_i_table) in sub_tables {
+                    for sub_i_table in sub_tables.values() {
                         InternalTable::stringify_sub_table(
                             toml,
                             indent_spaces,
@@ -141,7 +146,7 @@ impl InternalTable {
             KindOfTable::ArrayOfTable(k_aot) => {
                 for (i, sibling_table) in k_aot.tables.iter().enumerate() {
                     // Table header.
-                    toml.push_str(&indent_spaces);
+                    toml.push_str(indent_spaces);
                     toml.push_str(&format!(
                         "[[{}]]
 ",
@@ -150,7 +155,7 @@ impl InternalTable {
                     // Sorted map.
                     if let Some(sorted_map) = &sibling_table.sorted_map {
                         for (k2, formatted_v) in sorted_map {
-                            toml.push_str(&indent_spaces);
+                            toml.push_str(indent_spaces);
                             toml.push_str(&format!(
                                 "{} = {}
 ",
@@ -161,7 +166,7 @@ impl InternalTable {
                     // Sub tables.
                     if let Some(sub_tables) = &sibling_table.sub_tables {
                         indent_spaces.push_str("  ");
-                        for (_k1, sub_i_table) in sub_tables {
+                        for sub_i_table in sub_tables.values() {
                             InternalTable::stringify_sub_table(
                                 toml,
                                 indent_spaces,
@@ -179,11 +184,6 @@ impl InternalTable {
     }
 }
 
-impl Default for ArrayOfTable {
-    fn default() -> Self {
-        ArrayOfTable { tables: Vec::new() }
-    }
-}
 impl ArrayOfTable {
     /// Push a table.  
     /// テーブルを追加します。
@@ -234,9 +234,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -264,9 +264,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -295,9 +295,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -326,9 +326,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -357,9 +357,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -388,9 +388,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -408,7 +408,7 @@ impl Table {
         });
 
         if let Some(old) = old {
-            Table::print_already_use(key, &old, &value.to_string());
+            Table::print_already_use(key, &old, value);
         }
 
         self
@@ -419,9 +419,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -439,7 +439,7 @@ impl Table {
         });
 
         if let Some(old) = old {
-            Table::print_already_use(key, &old, &value.to_string());
+            Table::print_already_use(key, &old, value);
         }
 
         self
@@ -450,9 +450,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `base_name` - Sub table name.  
-    ///                 サブ・テーブル名。  
+    ///   サブ・テーブル名。  
     /// * `table` - Sub table.  
-    ///             サブ・テーブル。  
+    ///   サブ・テーブル。  
     ///
     /// # Returns
     ///
@@ -465,15 +465,15 @@ impl Table {
                 // Base name.
                 AutoCorrect::correct_key(base_name),
                 // Message.
-                InternalTable::from_sub_table(&AutoCorrect::correct_key(base_name), &sub_table),
+                InternalTable::from_sub_table(&AutoCorrect::correct_key(base_name), sub_table),
             );
         });
 
-        if let Some(_) = old {
+        if old.is_some() {
             Table::print_already_use(
                 &AutoCorrect::correct_key(base_name),
-                &"...Omitted...",
-                &"...Omitted...",
+                "...Omitted...",
+                "...Omitted...",
             );
         }
 
@@ -485,9 +485,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `base_name` - Array of table name.  
-    ///                 テーブルの配列名。  
+    ///   テーブルの配列名。  
     /// * `table` - Array of table.  
-    ///             テーブルの配列。  
+    ///   テーブルの配列。  
     ///
     /// # Returns
     ///
@@ -500,15 +500,15 @@ impl Table {
                 // Base name.
                 AutoCorrect::correct_key(base_name),
                 // Message.
-                InternalTable::from_aot(&AutoCorrect::correct_key(base_name), &aot),
+                InternalTable::from_aot(&AutoCorrect::correct_key(base_name), aot),
             );
         });
 
-        if let Some(_) = old {
+        if old.is_some() {
             Table::print_already_use(
                 &AutoCorrect::correct_key(base_name),
-                &"...Omitted...",
-                &"...Omitted...",
+                "...Omitted...",
+                "...Omitted...",
             );
         }
 
@@ -520,9 +520,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -551,9 +551,9 @@ impl Table {
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     /// * `value` - A value.  
-    ///             値。  
+    ///   値。  
     ///
     /// # Returns
     ///
@@ -579,16 +579,11 @@ impl Table {
     /// Key duplicate message.
     /// キーの重複メッセージ。
     fn print_already_use(key: &str, old: &str, value: &str) {
-        if let Ok(opt) = Log::get_opt() {
-            match opt {
-                Opt::BeginnersSupport | Opt::Development => {
-                    println!(
-                        "casual_logger   | |{}| is already use. |{}| is is overwritten by |{}|.",
-                        key, old, value
-                    );
-                }
-                _ => {} // Ignored it.
-            }
+        if let Ok(Opt::BeginnersSupport | Opt::Development) = Log::get_opt() {
+            println!(
+                "casual_logger   | |{}| is already use. |{}| is is overwritten by |{}|.",
+                key, old, value
+            );
         }
     }
 }
@@ -0,0 +1,65 @@
+//! Values for the logging macros.  
+//! ロギング・マクロの値。  
+use crate::{Table, TableValue};
+
+impl<T: TableValue + ?Sized> TableValue for &T {
+    fn insert_into(&self, table: &mut Table, key: &str) {
+        (**self).insert_into(table, key);
+    }
+}
+impl TableValue for str {
+    fn insert_into(&self, table: &mut Table, key: &str) {
+        table.str(key, self);
+    }
+}
+impl TableValue for String {
+    fn insert_into(&self, table: &mut Table, key: &str) {
+        table.str(key, self);
+    }
+}
+impl TableValue for bool {
+    fn insert_into(&self, table: &mut Table, key: &str) {
+        table.bool(key, *self);
+    }
+}
+impl TableValue for char {
+    fn insert_into(&self, table: &mut Table, key: &str) {
+        table.char(key, *self);
+    }
+}
+impl TableValue for f32 {
+    fn insert_into(&self, table: &mut Table, key: &str) {
+        table.float(key, *self as f64);
+    }
+}
+impl TableValue for f64 {
+    fn insert_into(&self, table: &mut Table, key: &str) {
+        table.float(key, *self);
+    }
+}
+
+/// Signed integers go in with `Table::int()`.  
+/// 符号付き整数は `Table::int()` で入れます。  
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(impl TableValue for $t {
+            fn insert_into(&self, table: &mut Table, key: &str) {
+                table.int(key, *self as i128);
+            }
+        })*
+    };
+}
+impl_int!(i8, i16, i32, i64, i128, isize);
+
+/// Unsigned integers go in with `Table::uint()`.  
+/// 符号無し整数は `Table::uint()` で入れます。  
+macro_rules! impl_uint {
+    ($($t:ty),*) => {
+        $(impl TableValue for $t {
+            fn insert_into(&self, table: &mut Table, key: &str) {
+                table.uint(key, *self as u128);
+            }
+        })*
+    };
+}
+impl_uint!(u8, u16, u32, u64, u128, usize);
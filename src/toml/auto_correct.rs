@@ -1,6 +1,5 @@
-use crate::logger::Logger;
 use crate::stringifier::Stringifier;
-use crate::Opt;
+use crate::{Log, Opt, DEFAULT_OPTIMIZATION};
 use regex::Regex;
 use std::sync::Mutex;
 
@@ -13,23 +12,23 @@ lazy_static! {
 pub struct AutoCorrect {}
 impl AutoCorrect {
     /// Correct the key automatically.  
+    /// A table is made before it is given to a logger, so the optimization of `Log::` is used.  
     /// キーを補正します。  
+    /// テーブルはロガーに渡す前に作るので、 `Log::` の最適化を使います。  
     ///
     /// # Arguments
     ///
     /// * `key` - A key.  
-    ///             キー。  
+    ///   キー。  
     ///
     /// # Returns
     ///
     /// Table.  
     /// テーブル。  
     pub fn correct_key(key: &str) -> String {
-        match Logger::get_optimization() {
-            Opt::Release => {
-                return key.to_string();
-            }
-            _ => {}
+        let opt = Log::get_opt().unwrap_or(DEFAULT_OPTIMIZATION);
+        if opt == Opt::Release {
+            return key.to_string();
         }
 
         // Check
@@ -48,7 +47,6 @@ impl AutoCorrect {
                 "\"{}\"",
                 Stringifier::escape_double_quotation(&re_white_space.replace_all(key, " "))
             );
-            let opt = Logger::get_optimization();
             match opt {
                 Opt::BeginnersSupport | Opt::Development => {
                     println!(
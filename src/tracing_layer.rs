@@ -0,0 +1,176 @@
+//! Backend of the `tracing` crate.  
+//! `tracing` クレートのバックエンド。  
+use crate::{Level, Log, Table, TracingLayer};
+use std::collections::BTreeMap;
+use std::fmt;
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// A value of a field.  
+/// フィールドの値。  
+enum FieldValue {
+    Str(String),
+    Int(i64),
+    Uint(u64),
+    Bool(bool),
+    Float(f64),
+}
+
+/// The fields of an event or a span. A span keeps them in its extensions.  
+/// Recording a field again overwrites it.  
+/// イベントかスパンのフィールド。スパンは拡張領域に保持します。  
+/// フィールドを再び記録すると上書きします。  
+#[derive(Default)]
+struct Fields {
+    values: BTreeMap<&'static str, FieldValue>,
+    message: Option<String>,
+}
+impl Fields {
+    fn to_table(&self) -> Table {
+        let mut table = Table::default();
+        for (name, value) in &self.values {
+            match value {
+                FieldValue::Str(value) => table.str(name, value),
+                FieldValue::Int(value) => table.int(name, *value as i128),
+                FieldValue::Uint(value) => table.uint(name, *value as u128),
+                FieldValue::Bool(value) => table.bool(name, *value),
+                FieldValue::Float(value) => table.float(name, *value),
+            };
+        }
+        table
+    }
+}
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_str(field, &format!("{:?}", value));
+    }
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            self.values
+                .insert(field.name(), FieldValue::Str(value.to_string()));
+        }
+    }
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.values.insert(field.name(), FieldValue::Int(value));
+    }
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.values.insert(field.name(), FieldValue::Uint(value));
+    }
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.values.insert(field.name(), FieldValue::Bool(value));
+    }
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.values.insert(field.name(), FieldValue::Float(value));
+    }
+}
+
+impl TracingLayer {
+    /// Add it to a `tracing_subscriber::registry()`.  
+    /// `tracing_subscriber::registry()` に追加してください。  
+    pub fn new() -> Self {
+        TracingLayer::default()
+    }
+}
+
+impl<S> Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            let mut fields = Fields::default();
+            attrs.record(&mut fields);
+            span.extensions_mut().insert(fields);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(fields) = span.extensions_mut().get_mut::<Fields>() {
+                values.record(fields);
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = to_level(*metadata.level());
+        if !Log::enabled_for(level, metadata.target()) {
+            return;
+        }
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let mut table = fields.to_table();
+        let message = fields.message.unwrap_or_default();
+
+        // From the innermost span to the root, each one is a sub table of its parent.
+        // 一番内側のスパンからルートへ、それぞれが親のサブ・テーブルになります。
+        let mut sub: Option<(String, Table)> = None;
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope {
+                let mut span_table = match span.extensions().get::<Fields>() {
+                    Some(fields) => fields.to_table(),
+                    None => Table::default(),
+                };
+                if let Some((name, child)) = &sub {
+                    span_table.sub_t(name, child);
+                }
+                sub = Some((span.name().to_string(), span_table));
+            }
+        }
+        if let Some((name, root)) = &sub {
+            table.sub_t(name, root);
+        }
+
+        table.str("Target", metadata.target());
+        if let Some(module_path) = metadata.module_path() {
+            table.str("ModulePath", module_path);
+        }
+        if let Some(file) = metadata.file() {
+            table.str("File", file);
+        }
+        if let Some(line) = metadata.line() {
+            table.uint("Line", line as u128);
+        }
+        Log::log_t(level, metadata.target(), &message, &mut table);
+    }
+}
+
+/// `tracing` has no Notice and Fatal.  
+/// `tracing_core::Level` is a struct of constants, not an enum,  
+/// so it is compared by its order, from the most verbose.  
+/// `tracing` には Notice と Fatal がありません。  
+/// `tracing_core::Level` は列挙型ではなく定数の構造体なので、  
+/// 一番詳しいものから順序で比べます。  
+fn to_level(level: tracing_core::Level) -> Level {
+    if tracing_core::Level::TRACE <= level {
+        Level::Trace
+    } else if tracing_core::Level::DEBUG <= level {
+        Level::Debug
+    } else if tracing_core::Level::INFO <= level {
+        Level::Info
+    } else if tracing_core::Level::WARN <= level {
+        Level::Warn
+    } else {
+        Level::Error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_level_has_the_level_of_the_same_name() {
+        assert_eq!(to_level(tracing_core::Level::TRACE), Level::Trace);
+        assert_eq!(to_level(tracing_core::Level::DEBUG), Level::Debug);
+        assert_eq!(to_level(tracing_core::Level::INFO), Level::Info);
+        assert_eq!(to_level(tracing_core::Level::WARN), Level::Warn);
+        assert_eq!(to_level(tracing_core::Level::ERROR), Level::Error);
+    }
+}
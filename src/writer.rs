@@ -0,0 +1,560 @@
+//! Dedicated writer thread.  
+//! 書き込み専用のスレッド。  
+use crate::instance::Instance;
+use crate::logger::Logger;
+use crate::stringifier::Stringifier;
+use crate::table::InternalTable;
+use crate::{
+    Error, Level, Log, Overflow, Table, WriteMode, DEFAULT_OVERFLOW, DEFAULT_QUEUE_CAPACITY,
+    DEFAULT_WRITE_MODE,
+};
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, Once, PoisonError};
+use std::thread;
+
+/// Maximum number of tables written at once.  
+/// 一度に書き込むテーブルの最大数。  
+const BATCH_SIZE: usize = 10_000;
+
+/// Message to the writer thread.  
+/// 書き込みスレッドへのメッセージ。  
+enum Message {
+    /// Table to write.  
+    /// 書き込むテーブル。  
+    Table(InternalTable),
+    /// Number of tables dropped because the queue was full.  
+    /// キューが満杯で捨てたテーブルの数。  
+    Dropped(u64),
+    /// Reply after all the tables sent before this are written.  
+    /// If true, the file is also synchronized to the disk.  
+    /// これより前に送られたテーブルを全部書いたら返信します。  
+    /// true なら、ファイルをディスクへ同期もします。  
+    Flush(Sender<()>, bool),
+}
+
+/// Messages waiting for the writer thread.  
+/// 書き込みスレッドを待っているメッセージ。  
+struct Queue {
+    messages: VecDeque<Message>,
+    /// Number of tables in the messages.  
+    /// メッセージの中のテーブルの数。  
+    tables: usize,
+    /// Number of tables dropped since the last notice.  
+    /// 前回の通知から捨てたテーブルの数。  
+    dropped: u64,
+    /// The writer thread ends when the queue is empty.  
+    /// キューが空になったら書き込みスレッドは終わります。  
+    stopped: bool,
+    /// Maximum number of tables in the queue. 0 is unlimited.  
+    /// キューの中のテーブルの最大数。 0 は無制限。  
+    capacity: usize,
+    /// What to do when the queue is full.  
+    /// キューが満杯のときにどうするか。  
+    overflow: Overflow,
+}
+impl Default for Queue {
+    fn default() -> Self {
+        Queue {
+            messages: VecDeque::new(),
+            tables: 0,
+            dropped: 0,
+            stopped: false,
+            capacity: DEFAULT_QUEUE_CAPACITY,
+            overflow: DEFAULT_OVERFLOW,
+        }
+    }
+}
+
+/// What to do with a new table, by the overflow policy.  
+/// 新しいテーブルをどうするか。あふれたときの方針によります。  
+#[derive(Debug, PartialEq)]
+enum Admit {
+    /// There is space.  
+    /// 空きがあります。  
+    Push,
+    /// The oldest table was dropped to make space. Check again.  
+    /// 空けるために一番古いテーブルを捨てました。もう一度検査します。  
+    Evicted,
+    /// Drop the new table.  
+    /// 新しいテーブルを捨てます。  
+    Drop,
+    /// Wait for space.  
+    /// 空きを待ちます。  
+    Wait,
+}
+impl Queue {
+    /// Make space for a new table, as the overflow policy says.  
+    /// あふれたときの方針に従って、新しいテーブルのために空けます。  
+    fn admit(&mut self, level: Option<Level>) -> Admit {
+        if self.capacity < 1 || self.tables < self.capacity {
+            return Admit::Push;
+        }
+        match self.overflow {
+            Overflow::Block => Admit::Wait,
+            Overflow::DropNewest => Admit::Drop,
+            Overflow::DropOldest => {
+                match self
+                    .messages
+                    .iter()
+                    .position(|m| matches!(m, Message::Table(_)))
+                {
+                    Some(index) => {
+                        self.messages.remove(index);
+                        self.tables -= 1;
+                        Admit::Evicted
+                    }
+                    None => Admit::Push,
+                }
+            }
+            Overflow::DropBelow(limit) => match level {
+                Some(level) if limit.number() < level.number() => Admit::Drop,
+                _ => Admit::Wait,
+            },
+        }
+    }
+}
+
+// Writer threads never wait for space in a queue.
+// 書き込みスレッドはキューの空きを待ちません。
+thread_local!(static IS_WRITER: Cell<bool> = const { Cell::new(false) });
+
+/// Dedicated writer thread of a logger.  
+/// Tables are written in the order they were sent.  
+/// ロガーの書き込み専用のスレッド。  
+/// テーブルは送った順に書き込まれます。  
+pub struct Writer {
+    queue: Mutex<Queue>,
+    /// The writer thread waits for messages.  
+    arrived: Condvar,
+    /// The callers wait for space in the queue.  
+    space: Condvar,
+    /// The writer thread starts at the first use.  
+    start: Once,
+    /// False if the writer thread could not start.  
+    running: AtomicBool,
+    /// Number of tables not yet written.  
+    /// まだ書き込まれていないテーブルの数。  
+    pending: AtomicUsize,
+    /// Number of tables dropped because the queue was full.  
+    /// キューが満杯で捨てたテーブルの数。  
+    dropped: AtomicU64,
+    /// `WriteMode::Synchronous`. It is read at each log without locking the settings.  
+    /// `WriteMode::Synchronous` 。ログのたびに、設定をロックせずに読みます。  
+    synchronous: AtomicBool,
+}
+impl Default for Writer {
+    fn default() -> Self {
+        Writer {
+            queue: Mutex::new(Queue::default()),
+            arrived: Condvar::new(),
+            space: Condvar::new(),
+            start: Once::new(),
+            running: AtomicBool::new(false),
+            pending: AtomicUsize::new(0),
+            dropped: AtomicU64::new(0),
+            synchronous: AtomicBool::new(matches!(DEFAULT_WRITE_MODE, WriteMode::Synchronous)),
+        }
+    }
+}
+impl Writer {
+    fn lock_queue(&self) -> MutexGuard<'_, Queue> {
+        // No user code runs under this lock, so the queue is consistent even if poisoned.
+        // このロックの下ではユーザーのコードは動かないので、毒されてもキューは壊れていません。
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Number of tables not yet written.  
+    /// まだ書き込まれていないテーブルの数。  
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Number of tables dropped because the queue was full.  
+    /// キューが満杯で捨てたテーブルの数。  
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::SeqCst)
+    }
+
+    /// How to write the log.  
+    /// ログをどう書き込むか。  
+    pub fn write_mode(&self) -> WriteMode {
+        if self.synchronous.load(Ordering::SeqCst) {
+            WriteMode::Synchronous
+        } else {
+            WriteMode::Background
+        }
+    }
+
+    /// Take the settings of the queue from the logger.  
+    /// The logger is locked by the caller, not by logging.  
+    /// キューの設定をロガーから取ります。  
+    /// ロガーをロックするのは呼び出し元で、ロギングではありません。  
+    pub fn configure(&self, logger: &Logger) {
+        self.synchronous.store(
+            matches!(logger.write_mode, WriteMode::Synchronous),
+            Ordering::SeqCst,
+        );
+        let mut queue = self.lock_queue();
+        queue.capacity = logger.queue_capacity;
+        queue.overflow = logger.overflow;
+        // A larger capacity may have made space.
+        // 容量が増えて空いたかもしれません。
+        self.space.notify_all();
+    }
+
+    /// The writer thread ends after writing what is left.  
+    /// 書き込みスレッドは残りを書いてから終わります。  
+    pub fn stop(&self) {
+        self.lock_queue().stopped = true;
+        self.arrived.notify_one();
+    }
+
+    /// Space has returned. Record how many were dropped.  
+    /// 空きが戻りました。いくつ捨てたか記録します。  
+    fn push_dropped(queue: &mut Queue) {
+        if 0 < queue.dropped {
+            let dropped = queue.dropped;
+            queue.dropped = 0;
+            queue.messages.push_back(Message::Dropped(dropped));
+        }
+    }
+
+    fn count_dropped(&self, queue: &mut Queue) {
+        queue.dropped += 1;
+        self.dropped.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn create_dropped_notice(dropped: u64, seq: u128) -> InternalTable {
+        let mut table = Table::new(
+            Level::Warn,
+            "casual_logger dropped logs because the queue was full.",
+            false,
+            &Stringifier::create_identify_table_name(seq),
+        );
+        table.uint("Dropped", dropped as u128);
+        InternalTable::from_table(&table)
+    }
+
+    /// Wait for the first message, then take all that have arrived.  
+    /// 最初のメッセージを待ち、届いているものを全部取ります。  
+    ///
+    /// # Returns
+    ///
+    /// None if stopped and nothing is left.  
+    /// 止められて、何も残っていなければ None 。  
+    fn take(&self) -> Option<Vec<Message>> {
+        let mut queue = self.lock_queue();
+        while queue.messages.is_empty() {
+            if queue.stopped {
+                return None;
+            }
+            queue = self
+                .arrived
+                .wait(queue)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        let len = queue.messages.len().min(BATCH_SIZE);
+        let messages: Vec<Message> = queue.messages.drain(..len).collect();
+        queue.tables -= messages
+            .iter()
+            .filter(|m| matches!(m, Message::Table(_)))
+            .count();
+        self.space.notify_all();
+        Some(messages)
+    }
+}
+
+impl Instance {
+    /// Start the writer thread, if not yet.  
+    /// まだなら、書き込みスレッドを起動します。  
+    ///
+    /// # Returns
+    ///
+    /// False if the writer thread could not start.  
+    /// Then write on the calling thread.  
+    /// 書き込みスレッドが起動できなかったら false 。  
+    /// そのときは呼び出し元のスレッドで書き込みます。  
+    fn is_running(self: &Arc<Self>) -> bool {
+        self.writer.start.call_once(|| {
+            let instance = Arc::clone(self);
+            let running = thread::Builder::new()
+                .name("casual_logger".to_string())
+                .spawn(move || instance.run())
+                .is_ok();
+            self.writer.running.store(running, Ordering::SeqCst);
+        });
+        self.writer.running.load(Ordering::SeqCst)
+    }
+
+    /// Send a table to the writer thread.  
+    /// The capacity and the overflow policy are in the queue, so the settings are not locked.  
+    /// テーブルを書き込みスレッドへ送ります。  
+    /// 容量とあふれたときの方針はキューにあるので、設定はロックしません。  
+    pub fn send(self: &Arc<Self>, i_table: InternalTable) {
+        if !self.is_running() {
+            // The writer thread is not running. Write on this thread.
+            // 書き込みスレッドが動いていません。このスレッドで書き込みます。
+            self.write_here(i_table);
+            return;
+        }
+
+        let writer = &self.writer;
+        let mut queue = writer.lock_queue();
+        let mut has_space = true;
+        // The writer thread must not wait for itself.
+        // 書き込みスレッドは自分自身を待ってはいけません。
+        let is_writer = IS_WRITER.try_with(|w| w.get()).unwrap_or(false);
+        loop {
+            match queue.admit(i_table.level()) {
+                Admit::Push => break,
+                Admit::Evicted => {
+                    writer.pending.fetch_sub(1, Ordering::SeqCst);
+                    writer.count_dropped(&mut queue);
+                    has_space = false;
+                }
+                Admit::Drop => {
+                    writer.count_dropped(&mut queue);
+                    return;
+                }
+                Admit::Wait => {
+                    if is_writer {
+                        writer.count_dropped(&mut queue);
+                        return;
+                    }
+                    queue = writer
+                        .space
+                        .wait(queue)
+                        .unwrap_or_else(PoisonError::into_inner);
+                }
+            }
+        }
+
+        if has_space {
+            Writer::push_dropped(&mut queue);
+        }
+        writer.pending.fetch_add(1, Ordering::SeqCst);
+        queue.tables += 1;
+        queue.messages.push_back(Message::Table(i_table));
+        writer.arrived.notify_one();
+    }
+
+    /// Write a table on the calling thread.  
+    /// 呼び出し元のスレッドでテーブルを書き込みます。  
+    pub fn write_here(&self, i_table: InternalTable) {
+        self.writer.pending.fetch_add(1, Ordering::SeqCst);
+        self.write(&[i_table]);
+    }
+
+    /// Ask the writer thread to reply after all the tables sent before are written.  
+    /// 前に送ったテーブルを全部書いたら返信するよう、書き込みスレッドに頼みます。  
+    ///
+    /// # Arguments
+    ///
+    /// * `sync` - Synchronize the file to the disk too.  
+    ///   ファイルをディスクへ同期もします。  
+    ///
+    /// # Returns
+    ///
+    /// Receiver of the reply. None if nothing is waiting or the writer thread  
+    /// is not running, then everything has already been written.  
+    /// 返信の受信側。待っているものがないか、書き込みスレッドが動いていなければ  
+    /// None で、そのときは全部書き込み済みです。  
+    pub fn flush(self: &Arc<Self>, sync: bool) -> Option<Receiver<()>> {
+        let writer = &self.writer;
+        let mut queue = writer.lock_queue();
+        // Do not start the thread only to flush.
+        // Only the drops not reported yet need a notice.
+        // フラッシュのためだけにスレッドを起動しません。
+        // 通知が要るのは、まだ報告していない破棄だけです。
+        if (writer.pending() == 0 && queue.dropped == 0) || !self.is_running() {
+            drop(queue);
+            if sync {
+                self.sync();
+            }
+            return None;
+        }
+        let (sender, receiver) = channel();
+        Writer::push_dropped(&mut queue);
+        queue.messages.push_back(Message::Flush(sender, sync));
+        writer.arrived.notify_one();
+        Some(receiver)
+    }
+
+    fn run(&self) {
+        IS_WRITER.with(|w| w.set(true));
+        while let Some(messages) = self.writer.take() {
+            let mut batch = Vec::new();
+            for message in messages {
+                match message {
+                    Message::Table(i_table) => batch.push(i_table),
+                    Message::Dropped(dropped) => {
+                        batch.push(Writer::create_dropped_notice(dropped, self.create_seq()));
+                        // It was not counted as pending, but write() subtracts it.
+                        self.writer.pending.fetch_add(1, Ordering::SeqCst);
+                    }
+                    Message::Flush(reply, sync) => {
+                        self.write(&batch);
+                        batch.clear();
+                        if sync {
+                            self.sync();
+                        }
+                        // The caller may have given up by the timeout.
+                        // 呼び出し元はタイムアウトで諦めているかもしれません。
+                        let _ = reply.send(());
+                    }
+                }
+            }
+            self.write(&batch);
+        }
+    }
+
+    /// Synchronize the file to the disk.  
+    /// ファイルをディスクへ同期します。  
+    fn sync(&self) {
+        let result = match self.file.lock() {
+            Ok(mut file) => file.sync(),
+            Err(e) => Err(Error::from(e)),
+        };
+        if let Err(error) = result {
+            Log::report_error(error);
+        }
+    }
+
+    fn write(&self, batch: &[InternalTable]) {
+        if batch.is_empty() {
+            return;
+        }
+        // By buffering, the number of file writes is reduced.
+        let mut str_buf = String::new();
+        // The most important level in the batch.
+        let mut top_level: Option<Level> = None;
+        for i_table in batch {
+            str_buf.push_str(&i_table.stringify());
+            match (top_level, i_table.level()) {
+                (Some(top), Some(level)) if level.number() < top.number() => {
+                    top_level = Some(level);
+                }
+                (None, Some(level)) => top_level = Some(level),
+                _ => {}
+            }
+        }
+        // Write to a log file.
+        // The settings are copied, so that they are not locked during file I/O.
+        // ファイル入出力の間ロックしないよう、設定はコピーします。
+        let result = match self.logger.lock().map(|logger| logger.clone()) {
+            Ok(logger) => match self.file.lock() {
+                Ok(mut file) => {
+                    let needs_sync = match (logger.sync_level, top_level) {
+                        (Some(sync_level), Some(level)) => level.number() <= sync_level.number(),
+                        _ => false,
+                    };
+                    file.write(&logger, &str_buf).and_then(|_| {
+                        if needs_sync {
+                            file.sync()
+                        } else {
+                            Ok(())
+                        }
+                    })
+                }
+                Err(e) => Err(Error::from(e)),
+            },
+            Err(e) => Err(Error::from(e)),
+        };
+        self.writer.pending.fetch_sub(batch.len(), Ordering::SeqCst);
+        if let Err(error) = result {
+            // Nothing is printed, because stdout may belong to the application.
+            // The error goes to `Log::last_error()` and the error callback.
+            // 標準出力はアプリケーションのものかもしれないので、何も表示しません。
+            // エラーは `Log::last_error()` とエラー・コールバックへ行きます。
+            Log::report_error(error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(level: Level) -> Message {
+        Message::Table(InternalTable::from_table(&Table::new(
+            level,
+            "test",
+            false,
+            &Stringifier::create_identify_table_name(0),
+        )))
+    }
+
+    fn full_queue(overflow: Overflow) -> Queue {
+        let mut queue = Queue {
+            capacity: 2,
+            overflow,
+            ..Queue::default()
+        };
+        queue.messages.push_back(table(Level::Info));
+        queue.messages.push_back(table(Level::Warn));
+        queue.tables = 2;
+        queue
+    }
+
+    fn levels(queue: &Queue) -> Vec<Option<usize>> {
+        queue
+            .messages
+            .iter()
+            .map(|message| match message {
+                Message::Table(i_table) => i_table.level().map(|level| level.number()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unlimited_or_not_full_queue_accepts() {
+        let mut queue = full_queue(Overflow::DropNewest);
+        queue.capacity = 0;
+        assert_eq!(queue.admit(Some(Level::Trace)), Admit::Push);
+        queue.capacity = 3;
+        assert_eq!(queue.admit(Some(Level::Trace)), Admit::Push);
+    }
+
+    #[test]
+    fn block_waits_for_space() {
+        let mut queue = full_queue(Overflow::Block);
+        assert_eq!(queue.admit(Some(Level::Fatal)), Admit::Wait);
+        assert_eq!(queue.tables, 2);
+    }
+
+    #[test]
+    fn drop_newest_drops_the_new_table() {
+        let mut queue = full_queue(Overflow::DropNewest);
+        assert_eq!(queue.admit(Some(Level::Fatal)), Admit::Drop);
+        assert_eq!(
+            levels(&queue),
+            vec![Some(Level::Info.number()), Some(Level::Warn.number())]
+        );
+    }
+
+    #[test]
+    fn drop_oldest_drops_the_oldest_table() {
+        let mut queue = full_queue(Overflow::DropOldest);
+        // A flush request is not a table, so it is not dropped.
+        queue
+            .messages
+            .push_front(Message::Flush(channel().0, false));
+        assert_eq!(queue.admit(Some(Level::Trace)), Admit::Evicted);
+        assert_eq!(queue.tables, 1);
+        assert_eq!(levels(&queue), vec![None, Some(Level::Warn.number())]);
+        assert_eq!(queue.admit(Some(Level::Trace)), Admit::Push);
+    }
+
+    #[test]
+    fn drop_below_waits_only_for_important_tables() {
+        let mut queue = full_queue(Overflow::DropBelow(Level::Warn));
+        assert_eq!(queue.admit(Some(Level::Error)), Admit::Wait);
+        assert_eq!(queue.admit(Some(Level::Warn)), Admit::Wait);
+        assert_eq!(queue.admit(Some(Level::Info)), Admit::Drop);
+        assert_eq!(queue.admit(None), Admit::Wait);
+    }
+}
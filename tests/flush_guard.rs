@@ -0,0 +1,25 @@
+//! The guard flushes `Log::`, so it is tested in its own process.
+//! ガードは `Log::` をフラッシュするので、専用のプロセスでテストします。
+use casual_logger::{Level, Log};
+use std::fs;
+
+#[test]
+fn dropping_the_guard_writes_everything_queued() {
+    let dir = std::env::temp_dir().join(format!("casual_logger-guard-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    Log::set_dir(&dir.to_string_lossy());
+    Log::set_file_name("guard");
+    Log::set_level(Level::Info);
+    {
+        let _guard = Log::init_guard();
+        for i in 0..500 {
+            Log::info(&format!("line {}", i));
+        }
+    }
+
+    let entry = fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+    let text = fs::read_to_string(entry.path()).unwrap();
+    assert_eq!(text.matches("&Seq=").count(), 500);
+    assert!(text.contains("line 499"), "{}", text);
+    let _ = fs::remove_dir_all(&dir);
+}
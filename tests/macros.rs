@@ -0,0 +1,36 @@
+//! The macros write with `Log::`, so they are tested in their own process.
+//! マクロは `Log::` で書くので、専用のプロセスでテストします。
+use casual_logger::{info, warn_t, Level, Log, WriteMode};
+use std::fs;
+
+#[test]
+fn macros_format_arguments_and_record_the_call_site() {
+    let dir = std::env::temp_dir().join(format!("casual_logger-macros-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    Log::set_dir(&dir.to_string_lossy());
+    Log::set_file_name("macros");
+    Log::set_write_mode(WriteMode::Synchronous);
+    Log::set_level(Level::Trace);
+
+    let x = 1;
+    let name = "inline";
+    info!("positional {} {}", x, "two");
+    info!("{name} arg");
+    info!("named {a}", a = 3);
+    let line = line!() + 1;
+    warn_t!("keys {}, {b}", x, b = 2; weight = 97.5, unit = "kg");
+    Log::flush();
+
+    let entry = fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+    let text = fs::read_to_string(entry.path()).unwrap();
+    assert!(text.contains("positional 1 two"), "{}", text);
+    assert!(text.contains("inline arg"), "{}", text);
+    assert!(text.contains("named 3"), "{}", text);
+    assert!(text.contains("keys 1, 2"), "{}", text);
+    assert!(text.contains(&format!("Line = {}", line)), "{}", text);
+    assert!(text.contains("File = 'tests/macros.rs'"), "{}", text);
+    assert!(text.contains("Module = 'macros'"), "{}", text);
+    assert!(text.contains("weight = 97.5"), "{}", text);
+    assert!(text.contains("unit = 'kg'"), "{}", text);
+    let _ = fs::remove_dir_all(&dir);
+}
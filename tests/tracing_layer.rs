@@ -0,0 +1,46 @@
+//! The layer writes with `Log::`, so it is tested in its own process.
+//! レイヤーは `Log::` で書くので、専用のプロセスでテストします。
+#![cfg(feature = "tracing")]
+use casual_logger::{Level, Log, TracingLayer, WriteMode};
+use std::fs;
+use tracing_subscriber::prelude::*;
+
+#[test]
+fn events_are_written_with_the_fields_of_the_nested_spans() {
+    let dir = std::env::temp_dir().join(format!("casual_logger-tracing-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    Log::set_dir(&dir.to_string_lossy());
+    Log::set_file_name("tracing");
+    Log::set_write_mode(WriteMode::Synchronous);
+    Log::set_level(Level::Debug);
+
+    let subscriber = tracing_subscriber::registry().with(TracingLayer::new());
+    tracing::subscriber::with_default(subscriber, || {
+        let request = tracing::info_span!("request", id = 7, path = "/play");
+        let _request = request.enter();
+        let turn = tracing::debug_span!("turn", player = "x", done = tracing::field::Empty);
+        let _turn = turn.enter();
+        turn.record("done", true);
+        tracing::warn!(elapsed = 1.5, count = 3u64, "Slow {}.", "move");
+        tracing::trace!("Below the level.");
+    });
+    Log::flush();
+
+    let entry = fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+    let text = fs::read_to_string(entry.path()).unwrap();
+    assert!(text.contains("Warn = 'Slow move.'"), "{}", text);
+    assert!(text.contains("Target = 'tracing_layer'"), "{}", text);
+    assert!(text.contains("elapsed = 1.5"), "{}", text);
+    assert!(text.contains("count = 3"), "{}", text);
+    // The outer span is a sub table of the event, and the inner one of the outer one.
+    let request = text.find("\".request]").expect(&text);
+    let turn = text.find("\".request.turn]").expect(&text);
+    assert!(request < turn, "{}", text);
+    let (outer, inner) = text[request..].split_at(turn - request);
+    assert!(outer.contains("id = 7"), "{}", text);
+    assert!(outer.contains("path = '/play'"), "{}", text);
+    assert!(inner.contains("player = 'x'"), "{}", text);
+    assert!(inner.contains("done = true"), "{}", text);
+    assert!(!text.contains("Below the level."), "{}", text);
+    let _ = fs::remove_dir_all(&dir);
+}